@@ -189,7 +189,7 @@ fn run_shell_command(adb_path: &str, serial: &str, cmd: &str) -> Option<String>
     }
 }
 
-fn get_prop(adb_path: &str, serial: &str, prop: &str) -> Option<String> {
+pub(crate) fn get_prop(adb_path: &str, serial: &str, prop: &str) -> Option<String> {
     run_shell_command(adb_path, serial, &format!("getprop {}", prop))
 }
 
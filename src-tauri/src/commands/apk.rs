@@ -0,0 +1,970 @@
+use crate::commands::adb::get_prop;
+use crate::commands::packages::fetch_package_details;
+use crate::commands::utils::{resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleFormat {
+    Apks,
+    Xapk,
+}
+
+impl BundleFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            BundleFormat::Apks => "apks",
+            BundleFormat::Xapk => "xapk",
+        }
+    }
+}
+
+/// What a split APK configures, derived from its file name
+/// (`split_config.arm64_v8a.apk`, `config.xxhdpi.apk`, ...).
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "lowercase")]
+pub enum SplitKind {
+    Base,
+    Abi(String),
+    Density(String),
+    Locale(String),
+    Feature(String),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SplitEntry {
+    pub file: String,
+    pub id: String,
+}
+
+/// Written as `manifest.json` at the root of `.apks`/`.xapk` bundles.
+/// Field names follow the XAPK layout so other installers can read it.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleManifest {
+    pub xapk_version: u32,
+    pub package_name: String,
+    pub version_code: String,
+    pub version_name: String,
+    #[serde(default)]
+    pub min_sdk_version: String,
+    #[serde(default)]
+    pub target_sdk_version: String,
+    pub split_apks: Vec<SplitEntry>,
+    #[serde(default)]
+    pub abis: Vec<String>,
+    #[serde(default)]
+    pub densities: Vec<String>,
+    #[serde(default)]
+    pub locales: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractedApk {
+    pub package_id: String,
+    pub version_name: String,
+    pub version_code: String,
+    pub directory: String,
+    pub files: Vec<String>,
+    pub bundle: Option<String>,
+}
+
+/// A `Failure [INSTALL_FAILED_*: message]` reported by the package manager.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallFailure {
+    pub code: String,
+    pub message: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SplitInstallResult {
+    pub file: String,
+    pub id: String,
+    pub success: bool,
+    pub failure: Option<InstallFailure>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleInstallResult {
+    pub success: bool,
+    pub splits: Vec<SplitInstallResult>,
+    /// Splits left out of the install because they target another ABI,
+    /// density or locale.
+    pub skipped: Vec<String>,
+    pub failure: Option<InstallFailure>,
+}
+
+/// How APK bytes reach the device. `Streamed` writes them into a `pm`
+/// install session over stdin; `Incremental` hands off to
+/// `adb install-multiple --incremental`, which needs a v4 signature.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMode {
+    #[default]
+    Streamed,
+    Incremental,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct InstallOptions {
+    pub downgrade: bool,
+    pub grant_all: bool,
+    pub test_only: bool,
+    pub user_id: Option<u32>,
+    pub instant: bool,
+    pub bypass_low_target_sdk_block: bool,
+    pub mode: InstallMode,
+}
+
+impl InstallOptions {
+    /// Flags shared by `pm install-create` and `adb install`.
+    fn flags(&self) -> Vec<String> {
+        let mut flags = vec!["-r".to_string()];
+        if self.downgrade {
+            flags.push("-d".to_string());
+        }
+        if self.grant_all {
+            flags.push("-g".to_string());
+        }
+        if self.test_only {
+            flags.push("-t".to_string());
+        }
+        if let Some(user) = self.user_id {
+            flags.push("--user".to_string());
+            flags.push(user.to_string());
+        }
+        if self.instant {
+            flags.push("--instant".to_string());
+        }
+        if self.bypass_low_target_sdk_block {
+            flags.push("--bypass-low-target-sdk-block".to_string());
+        }
+        flags
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallStage {
+    Writing,
+    Committing,
+    Completed,
+    Failed,
+}
+
+/// Payload of the `install-progress` event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InstallProgress {
+    pub device: String,
+    pub file: Option<String>,
+    pub stage: InstallStage,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+}
+
+/// Error returned by `install_package`: either the package manager rejected
+/// the APK, or adb itself could not be run.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum InstallError {
+    Failure(InstallFailure),
+    Adb { message: String },
+}
+
+impl From<String> for InstallError {
+    fn from(message: String) -> Self {
+        InstallError::Adb { message }
+    }
+}
+
+/// The device properties split APKs are matched against.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeviceSplitConfig {
+    pub abis: Vec<String>,
+    pub density: u32,
+    pub locales: Vec<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Emit an `install-progress` event at most once per this many bytes.
+const PROGRESS_STEP: u64 = 1024 * 1024;
+
+const ABIS: &[&str] = &[
+    "armeabi",
+    "armeabi_v7a",
+    "arm64_v8a",
+    "x86",
+    "x86_64",
+    "mips",
+    "mips64",
+    "riscv64",
+];
+
+const DENSITIES: &[&str] = &[
+    "ldpi", "mdpi", "tvdpi", "hdpi", "xhdpi", "xxhdpi", "xxxhdpi", "nodpi", "anydpi",
+];
+
+/// Split id as `pm` reports it: `split_config.en.apk` -> `config.en`.
+pub(crate) fn split_id(file_name: &str) -> String {
+    let stem = file_name.strip_suffix(".apk").unwrap_or(file_name);
+    stem.strip_prefix("split_").unwrap_or(stem).to_string()
+}
+
+pub(crate) fn classify_split(file_name: &str) -> SplitKind {
+    let id = split_id(file_name);
+    if id == "base" || id == "base-master" {
+        return SplitKind::Base;
+    }
+
+    // Config splits may belong to a feature module: `feature.config.xxhdpi`.
+    // bundletool names them `<module>-<config>` inside `.apks` archives.
+    let config = if let Some(idx) = id.find("config.") {
+        &id[idx + "config.".len()..]
+    } else if let Some((module, config)) = id.rsplit_once('-') {
+        if config == "master" {
+            return SplitKind::Feature(module.to_string());
+        }
+        config
+    } else {
+        return SplitKind::Feature(id);
+    };
+
+    if ABIS.contains(&config) {
+        SplitKind::Abi(config.to_string())
+    } else if DENSITIES.contains(&config) {
+        SplitKind::Density(config.to_string())
+    } else if looks_like_locale(config) {
+        SplitKind::Locale(config.to_string())
+    } else {
+        SplitKind::Feature(id)
+    }
+}
+
+/// `en`, `fil`, `pt_BR`, `zh-rTW`: a 2-3 letter language, optionally
+/// followed by a region or script.
+fn looks_like_locale(config: &str) -> bool {
+    let mut parts = config.split(['_', '-']);
+    let language = parts.next().unwrap_or("");
+    (2..=3).contains(&language.len())
+        && language.chars().all(|c| c.is_ascii_lowercase())
+        && parts.all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+/// Resolves every APK path (base and splits) of an installed package.
+pub(crate) fn package_apk_paths(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+) -> Result<Vec<String>, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell", "pm", "path", &shell_quote(package)])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let paths: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .map(|path| path.to_string())
+        .collect();
+
+    if paths.is_empty() {
+        return Err(format!("Package {} not found", package));
+    }
+    Ok(paths)
+}
+
+fn pull_file(adb_path: &str, device: &str, remote: &str, local: &Path) -> Result<(), String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "pull", remote])
+        .arg(local)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+fn write_bundle(
+    archive: &Path,
+    manifest: &BundleManifest,
+    files: &[PathBuf],
+) -> Result<(), String> {
+    let file = File::create(archive).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut zip = ZipWriter::new(file);
+
+    // APKs are already compressed, and installers expect to be able to
+    // stream them out of the archive without inflating.
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let manifest_json = serde_json::to_vec_pretty(manifest).map_err(|e| e.to_string())?;
+    zip.start_file("manifest.json", stored)
+        .map_err(|e| e.to_string())?;
+    zip.write_all(&manifest_json).map_err(|e| e.to_string())?;
+
+    for path in files {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        zip.start_file(name, stored).map_err(|e| e.to_string())?;
+        let mut apk = File::open(path).map_err(|e| e.to_string())?;
+        io::copy(&mut apk, &mut zip).map_err(|e| e.to_string())?;
+    }
+
+    zip.finish().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn is_bundle_archive(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    lower.ends_with(".apks") || lower.ends_with(".xapk") || lower.ends_with(".apkm")
+}
+
+/// Unpacks the APKs of a `.apks`/`.xapk`/`.apkm` archive into `dir`.
+/// bundletool's `standalones/` variants are skipped in favour of `splits/`.
+fn unpack_bundle(archive: &Path, dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let file = File::open(archive).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Invalid bundle archive: {}", e))?;
+    let mut apks = Vec::new();
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        let name = match entry.enclosed_name() {
+            Some(name) => name,
+            None => continue,
+        };
+        if entry.is_dir()
+            || name.extension().map(|ext| ext != "apk").unwrap_or(true)
+            || name.starts_with("standalones")
+        {
+            continue;
+        }
+
+        let file_name = match name.file_name() {
+            Some(file_name) => file_name.to_owned(),
+            None => continue,
+        };
+        let local = dir.join(file_name);
+        let mut out = File::create(&local).map_err(|e| e.to_string())?;
+        io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to unpack bundle: {}", e))?;
+        apks.push(local);
+    }
+
+    if apks.is_empty() {
+        return Err("Bundle does not contain any APKs".to_string());
+    }
+    Ok(apks)
+}
+
+pub(crate) fn device_split_config(adb_path: &str, device: &str) -> DeviceSplitConfig {
+    let abis = get_prop(adb_path, device, "ro.product.cpu.abilist")
+        .or_else(|| get_prop(adb_path, device, "ro.product.cpu.abi"))
+        .map(|list| list.split(',').map(|abi| abi.trim().to_string()).collect())
+        .unwrap_or_default();
+
+    // "Physical density: 440" optionally followed by "Override density: 400"
+    let density = Command::new(adb_path)
+        .args(["-s", device, "shell", "wm", "density"])
+        .output()
+        .ok()
+        .and_then(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split(':').nth(1))
+                .filter_map(|value| value.trim().parse::<u32>().ok())
+                .next_back()
+        })
+        .unwrap_or(160);
+
+    let mut locales = Vec::new();
+    for prop in ["persist.sys.locale", "ro.product.locale"] {
+        if let Some(locale) = get_prop(adb_path, device, prop) {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+    }
+
+    DeviceSplitConfig {
+        abis,
+        density,
+        locales,
+    }
+}
+
+fn density_bucket(dpi: u32) -> &'static str {
+    match dpi {
+        0..=120 => "ldpi",
+        121..=160 => "mdpi",
+        161..=213 => "tvdpi",
+        214..=240 => "hdpi",
+        241..=320 => "xhdpi",
+        321..=480 => "xxhdpi",
+        _ => "xxxhdpi",
+    }
+}
+
+/// Picks the splits a device would get from the Play Store: every feature
+/// split, the best ABI, the closest density and the matching languages.
+/// Returns the selected files and the names of those left out.
+fn select_splits(
+    files: Vec<PathBuf>,
+    config: &DeviceSplitConfig,
+) -> Result<(Vec<PathBuf>, Vec<String>), String> {
+    let kinds: Vec<SplitKind> = files
+        .iter()
+        .map(|path| classify_split(&path.file_name().unwrap_or_default().to_string_lossy()))
+        .collect();
+
+    let available_abis: Vec<&str> = kinds
+        .iter()
+        .filter_map(|kind| match kind {
+            SplitKind::Abi(abi) => Some(abi.as_str()),
+            _ => None,
+        })
+        .collect();
+    let chosen_abi = config
+        .abis
+        .iter()
+        .map(|abi| abi.replace('-', "_"))
+        .find(|abi| available_abis.contains(&abi.as_str()));
+    if !available_abis.is_empty() && chosen_abi.is_none() {
+        return Err(format!(
+            "No split matches the device ABIs ({})",
+            config.abis.join(", ")
+        ));
+    }
+
+    // Prefer the exact bucket, then the next larger one, then the largest
+    // smaller one, which is how the resource system scales drawables.
+    let available_densities: Vec<&str> = kinds
+        .iter()
+        .filter_map(|kind| match kind {
+            SplitKind::Density(density) => Some(density.as_str()),
+            _ => None,
+        })
+        .collect();
+    let buckets = &DENSITIES[..7];
+    let wanted = buckets
+        .iter()
+        .position(|b| *b == density_bucket(config.density))
+        .unwrap_or(1);
+    let chosen_density = buckets[wanted..]
+        .iter()
+        .chain(buckets[..wanted].iter().rev())
+        .find(|bucket| available_densities.contains(bucket))
+        .copied();
+
+    let languages: Vec<String> = config
+        .locales
+        .iter()
+        .filter_map(|locale| locale.split(['-', '_']).next())
+        .map(|language| language.to_lowercase())
+        .collect();
+
+    let mut selected = Vec::new();
+    let mut skipped = Vec::new();
+    for (path, kind) in files.into_iter().zip(kinds) {
+        let keep = match &kind {
+            SplitKind::Base | SplitKind::Feature(_) => true,
+            SplitKind::Abi(abi) => chosen_abi.as_deref() == Some(abi.as_str()),
+            SplitKind::Density(density) => {
+                chosen_density == Some(density.as_str()) || !buckets.contains(&density.as_str())
+            }
+            SplitKind::Locale(locale) => locale
+                .split(['-', '_'])
+                .next()
+                .map(|language| languages.contains(&language.to_string()))
+                .unwrap_or(false),
+        };
+        if keep {
+            selected.push(path);
+        } else {
+            skipped.push(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+    }
+
+    Ok((selected, skipped))
+}
+
+/// Short explanations for the failure codes testers run into most.
+fn describe_failure(code: &str) -> Option<&'static str> {
+    let description = match code {
+        "INSTALL_FAILED_ALREADY_EXISTS" => "The package is already installed",
+        "INSTALL_FAILED_INVALID_APK" => "The APK file is invalid",
+        "INSTALL_FAILED_INSUFFICIENT_STORAGE" => "Not enough storage on the device",
+        "INSTALL_FAILED_DUPLICATE_PACKAGE" => "A package with this name already exists",
+        "INSTALL_FAILED_UPDATE_INCOMPATIBLE" => {
+            "The installed version is signed with a different certificate"
+        }
+        "INSTALL_FAILED_SHARED_USER_INCOMPATIBLE" => "The shared user has a different signature",
+        "INSTALL_FAILED_MISSING_SHARED_LIBRARY" => "A required shared library is missing",
+        "INSTALL_FAILED_OLDER_SDK" => "The device SDK is lower than the app's minSdkVersion",
+        "INSTALL_FAILED_NEWER_SDK" => "The device SDK is higher than the app's maxSdkVersion",
+        "INSTALL_FAILED_TEST_ONLY" => "The APK is marked test-only",
+        "INSTALL_FAILED_CPU_ABI_INCOMPATIBLE" => "No native libraries for the device ABI",
+        "INSTALL_FAILED_MISSING_SPLIT" => "A required split APK is missing",
+        "INSTALL_FAILED_VERSION_DOWNGRADE" => "A newer version is already installed",
+        "INSTALL_FAILED_NO_MATCHING_ABIS" => "No native libraries for the device ABI",
+        "INSTALL_FAILED_USER_RESTRICTED" => "Installing apps is restricted for this user",
+        "INSTALL_FAILED_VERIFICATION_FAILURE" => "Package verification failed",
+        "INSTALL_FAILED_DEPRECATED_SDK_VERSION" => "The app targets an SDK that is too old",
+        "INSTALL_PARSE_FAILED_NO_CERTIFICATES" => "The APK is not signed",
+        "INSTALL_PARSE_FAILED_INCONSISTENT_CERTIFICATES" => {
+            "Splits are signed with different certificates"
+        }
+        "INSTALL_PARSE_FAILED_MANIFEST_MALFORMED" => "The manifest is malformed",
+        _ => return None,
+    };
+    Some(description)
+}
+
+/// Extracts `Failure [CODE: message]` (or `Error: ...`) from `pm` output.
+pub(crate) fn parse_pm_failure(output: &str) -> Option<InstallFailure> {
+    if let Some(start) = output.find("Failure [") {
+        let rest = &output[start + "Failure [".len()..];
+        let inner = rest.split(']').next().unwrap_or(rest);
+        let (code, message) = match inner.split_once(':') {
+            Some((code, message)) => (code.trim(), message.trim()),
+            None => (inner.trim(), ""),
+        };
+        return Some(InstallFailure {
+            code: code.to_string(),
+            message: message.to_string(),
+            description: describe_failure(code).map(|d| d.to_string()),
+        });
+    }
+
+    let error = output
+        .lines()
+        .find(|line| line.contains("Error:") || line.contains("Exception"))?;
+    Some(InstallFailure {
+        code: "INSTALL_FAILED_INTERNAL_ERROR".to_string(),
+        message: error.trim().to_string(),
+        description: None,
+    })
+}
+
+fn pm_session_command(adb_path: &str, device: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell", "pm"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+
+    Ok(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    ))
+}
+
+/// Streams one APK into an install session over stdin, the way
+/// `adb install-multiple` does, so each split gets its own result.
+/// `on_written` is called with the running byte count.
+fn write_session_split(
+    adb_path: &str,
+    device: &str,
+    session: &str,
+    index: usize,
+    path: &Path,
+    on_written: &dyn Fn(u64),
+) -> Result<(), InstallFailure> {
+    let failure = |message: String| InstallFailure {
+        code: "INSTALL_FAILED_INTERNAL_ERROR".to_string(),
+        message,
+        description: None,
+    };
+
+    let size = fs::metadata(path)
+        .map_err(|e| failure(e.to_string()))?
+        .len();
+    let name = format!(
+        "{}_{}",
+        index,
+        path.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let mut apk = File::open(path).map_err(|e| failure(e.to_string()))?;
+
+    let mut child = Command::new(adb_path)
+        .args(["-s", device, "shell", "pm", "install-write", "-S"])
+        .arg(size.to_string())
+        .args([session, &name, "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failure(format!("Failed to execute adb: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut written = 0u64;
+        let mut reported = 0u64;
+        loop {
+            let n = apk.read(&mut buf).map_err(|e| failure(e.to_string()))?;
+            if n == 0 {
+                break;
+            }
+            if stdin.write_all(&buf[..n]).is_err() {
+                // pm closed the stream early; its output says why
+                break;
+            }
+            written += n as u64;
+            if written - reported >= PROGRESS_STEP || written == size {
+                on_written(written);
+                reported = written;
+            }
+        }
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failure(format!("Failed to execute adb: {}", e)))?;
+    let result = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if result.contains("Success") {
+        Ok(())
+    } else {
+        Err(parse_pm_failure(&result).unwrap_or_else(|| failure(result.trim().to_string())))
+    }
+}
+
+fn emit_progress(
+    app: &AppHandle,
+    device: &str,
+    file: Option<String>,
+    stage: InstallStage,
+    bytes_written: u64,
+    total_bytes: u64,
+) {
+    let _ = app.emit(
+        "install-progress",
+        InstallProgress {
+            device: device.to_string(),
+            file,
+            stage,
+            bytes_written,
+            total_bytes,
+        },
+    );
+}
+
+/// Installs a set of APKs as a single package through a `pm` install
+/// session, reporting progress as `install-progress` events.
+pub(crate) fn install_session(
+    app: &AppHandle,
+    adb_path: &str,
+    device: &str,
+    files: &[PathBuf],
+    options: &InstallOptions,
+) -> Result<BundleInstallResult, String> {
+    if options.mode == InstallMode::Incremental {
+        return install_incremental(app, adb_path, device, files, options);
+    }
+
+    let total: u64 = files
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+
+    // "Success: created install session [1234]"
+    let mut create_args = vec!["install-create".to_string()];
+    create_args.extend(options.flags());
+    create_args.extend(["-S".to_string(), total.to_string()]);
+    let create_args: Vec<&str> = create_args.iter().map(|arg| arg.as_str()).collect();
+    let created = pm_session_command(adb_path, device, &create_args)?;
+    let session = match created
+        .split('[')
+        .nth(1)
+        .and_then(|rest| rest.split(']').next())
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+    {
+        Some(session) => session.to_string(),
+        None => {
+            emit_progress(app, device, None, InstallStage::Failed, 0, total);
+            return Ok(failed_result(
+                Vec::new(),
+                parse_pm_failure(&created).unwrap_or(InstallFailure {
+                    code: "INSTALL_FAILED_INTERNAL_ERROR".to_string(),
+                    message: format!("Failed to create install session: {}", created.trim()),
+                    description: None,
+                }),
+            ));
+        }
+    };
+
+    let mut splits = Vec::new();
+    let mut done = 0u64;
+    for (index, path) in files.iter().enumerate() {
+        let file = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let on_written = |written: u64| {
+            emit_progress(
+                app,
+                device,
+                Some(file.clone()),
+                InstallStage::Writing,
+                done + written,
+                total,
+            )
+        };
+        let result = write_session_split(adb_path, device, &session, index, path, &on_written);
+        done += fs::metadata(path).map(|meta| meta.len()).unwrap_or(0);
+        splits.push(SplitInstallResult {
+            id: split_id(&file),
+            file,
+            success: result.is_ok(),
+            failure: result.err(),
+        });
+    }
+
+    if let Some(failure) = splits.iter().find_map(|split| split.failure.clone()) {
+        let _ = pm_session_command(adb_path, device, &["install-abandon", &session]);
+        emit_progress(app, device, None, InstallStage::Failed, done, total);
+        return Ok(failed_result(splits, failure));
+    }
+
+    emit_progress(app, device, None, InstallStage::Committing, total, total);
+    let committed = pm_session_command(adb_path, device, &["install-commit", &session])?;
+    if !committed.contains("Success") {
+        emit_progress(app, device, None, InstallStage::Failed, total, total);
+        let failure = parse_pm_failure(&committed).unwrap_or(InstallFailure {
+            code: "INSTALL_FAILED_INTERNAL_ERROR".to_string(),
+            message: committed.trim().to_string(),
+            description: None,
+        });
+        return Ok(failed_result(splits, failure));
+    }
+
+    emit_progress(app, device, None, InstallStage::Completed, total, total);
+    Ok(BundleInstallResult {
+        success: true,
+        splits,
+        skipped: Vec::new(),
+        failure: None,
+    })
+}
+
+fn failed_result(splits: Vec<SplitInstallResult>, failure: InstallFailure) -> BundleInstallResult {
+    BundleInstallResult {
+        success: false,
+        splits,
+        skipped: Vec::new(),
+        failure: Some(failure),
+    }
+}
+
+/// Incremental installs stream blocks on demand through adb's incfs
+/// transport, so only adb itself can drive them.
+fn install_incremental(
+    app: &AppHandle,
+    adb_path: &str,
+    device: &str,
+    files: &[PathBuf],
+    options: &InstallOptions,
+) -> Result<BundleInstallResult, String> {
+    let total: u64 = files
+        .iter()
+        .filter_map(|path| fs::metadata(path).ok())
+        .map(|meta| meta.len())
+        .sum();
+    emit_progress(app, device, None, InstallStage::Writing, 0, total);
+
+    let output = Command::new(adb_path)
+        .args(["-s", device, "install-multiple", "--incremental"])
+        .args(options.flags())
+        .args(files)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let result = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let splits = files
+        .iter()
+        .map(|path| {
+            let file = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            SplitInstallResult {
+                id: split_id(&file),
+                file,
+                success: result.contains("Success"),
+                failure: None,
+            }
+        })
+        .collect();
+
+    if result.contains("Success") {
+        emit_progress(app, device, None, InstallStage::Completed, total, total);
+        Ok(BundleInstallResult {
+            success: true,
+            splits,
+            skipped: Vec::new(),
+            failure: None,
+        })
+    } else {
+        emit_progress(app, device, None, InstallStage::Failed, 0, total);
+        Ok(failed_result(
+            splits,
+            parse_pm_failure(&result).unwrap_or(InstallFailure {
+                code: "INSTALL_FAILED_INTERNAL_ERROR".to_string(),
+                message: result.trim().to_string(),
+                description: None,
+            }),
+        ))
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn extract_apk(
+    app: AppHandle,
+    device: String,
+    package: String,
+    dest: String,
+    bundle: Option<BundleFormat>,
+) -> Result<ExtractedApk, String> {
+    let adb_path = resolve_adb_path(&app);
+    let details = fetch_package_details(&adb_path, &device, &package, None)?;
+    let remote_paths = package_apk_paths(&adb_path, &device, &package)?;
+
+    let folder_name = format!("{}_{}", package, details.version_code);
+    let directory = Path::new(&dest).join(&folder_name);
+    fs::create_dir_all(&directory).map_err(|e| format!("Failed to create folder: {}", e))?;
+
+    let mut local_files = Vec::new();
+    let mut split_apks = Vec::new();
+    let mut abis = Vec::new();
+    let mut densities = Vec::new();
+    let mut locales = Vec::new();
+
+    for remote in &remote_paths {
+        let file_name = remote.rsplit('/').next().unwrap_or(remote).to_string();
+        let local = directory.join(&file_name);
+        pull_file(&adb_path, &device, remote, &local)?;
+
+        match classify_split(&file_name) {
+            SplitKind::Abi(abi) => abis.push(abi.replace('_', "-")),
+            SplitKind::Density(density) => densities.push(density),
+            SplitKind::Locale(locale) => locales.push(locale),
+            SplitKind::Base | SplitKind::Feature(_) => {}
+        }
+        split_apks.push(SplitEntry {
+            id: split_id(&file_name),
+            file: file_name,
+        });
+        local_files.push(local);
+    }
+
+    let bundle_path = match bundle {
+        Some(format) => {
+            let manifest = BundleManifest {
+                xapk_version: 2,
+                package_name: package.clone(),
+                version_code: details.version_code.clone(),
+                version_name: details.version_name.clone(),
+                min_sdk_version: details.min_sdk.clone(),
+                target_sdk_version: details.target_sdk.clone(),
+                split_apks,
+                abis,
+                densities,
+                locales,
+            };
+            let archive = Path::new(&dest).join(format!("{}.{}", folder_name, format.extension()));
+            write_bundle(&archive, &manifest, &local_files)?;
+            Some(archive.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+
+    Ok(ExtractedApk {
+        package_id: package,
+        version_name: details.version_name,
+        version_code: details.version_code,
+        directory: directory.to_string_lossy().to_string(),
+        files: local_files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        bundle: bundle_path,
+    })
+}
+
+/// Installs split APKs as one package. `paths` is either a list of `.apk`
+/// files or a single `.apks`/`.xapk`/`.apkm` bundle, in which case only the
+/// splits matching the device's ABI, density and locale are installed.
+#[tauri::command]
+pub async fn install_apk_bundle(
+    app: AppHandle,
+    device: String,
+    paths: Vec<String>,
+    options: Option<InstallOptions>,
+) -> Result<BundleInstallResult, String> {
+    let adb_path = resolve_adb_path(&app);
+    let options = options.unwrap_or_default();
+
+    if paths.len() == 1 && is_bundle_archive(&paths[0]) {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let dir = std::env::temp_dir().join(format!("green-bot-install-{}", stamp));
+        fs::create_dir_all(&dir).map_err(|e| format!("Failed to create temp folder: {}", e))?;
+
+        let result = unpack_bundle(Path::new(&paths[0]), &dir).and_then(|files| {
+            let config = device_split_config(&adb_path, &device);
+            let (selected, skipped) = select_splits(files, &config)?;
+            let mut result = install_session(&app, &adb_path, &device, &selected, &options)?;
+            result.skipped = skipped;
+            Ok(result)
+        });
+
+        let _ = fs::remove_dir_all(&dir);
+        return result;
+    }
+
+    let files: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    install_session(&app, &adb_path, &device, &files, &options)
+}
@@ -0,0 +1,298 @@
+use crate::commands::axml::{parse_xml, AttrValue, ResourceTable, XmlElement};
+use crate::commands::packages::fetch_package_details;
+use crate::commands::utils::resolve_adb_path;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use tauri::AppHandle;
+use zip::ZipArchive;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApkComponent {
+    pub kind: String, // "activity", "activity-alias", "service", "receiver", "provider"
+    pub name: String,
+    pub exported: Option<bool>,
+    pub is_launcher: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionComparison {
+    NotInstalled,
+    Upgrade,
+    Same,
+    Downgrade,
+    /// Installed, but one of the version codes could not be read.
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstalledVersion {
+    pub version_name: String,
+    pub version_code: String,
+    pub comparison: VersionComparison,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApkInfo {
+    pub package_id: String,
+    pub version_name: String,
+    pub version_code: String,
+    pub min_sdk: Option<i64>,
+    pub target_sdk: Option<i64>,
+    pub label: Option<String>,
+    /// Launcher icon as base64, when the APK ships a raster version of it.
+    pub icon: Option<String>,
+    pub icon_path: Option<String>,
+    pub permissions: Vec<String>,
+    pub components: Vec<ApkComponent>,
+    pub native_abis: Vec<String>,
+    pub file_size: u64,
+    pub installed: Option<InstalledVersion>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const COMPONENT_TAGS: &[&str] = &[
+    "activity",
+    "activity-alias",
+    "service",
+    "receiver",
+    "provider",
+];
+
+/// Largest zip entry read into memory. Manifests and resource tables are far
+/// smaller; anything bigger is treated as a zip bomb.
+pub(crate) const MAX_ZIP_ENTRY_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Reads an entry, or `None` if it is missing or inflates past
+/// `MAX_ZIP_ENTRY_SIZE`.
+pub(crate) fn read_zip_entry<R: Read + std::io::Seek>(
+    zip: &mut ZipArchive<R>,
+    name: &str,
+) -> Option<Vec<u8>> {
+    let entry = zip.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    entry
+        .take(MAX_ZIP_ENTRY_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .ok()?;
+    if bytes.len() as u64 > MAX_ZIP_ENTRY_SIZE {
+        return None;
+    }
+    Some(bytes)
+}
+
+fn has_launcher_filter(component: &XmlElement) -> bool {
+    component.children_named("intent-filter").any(|filter| {
+        let action = filter
+            .children_named("action")
+            .any(|a| a_name(a).as_deref() == Some("android.intent.action.MAIN"));
+        let category = filter
+            .children_named("category")
+            .any(|c| a_name(c).as_deref() == Some("android.intent.category.LAUNCHER"));
+        action && category
+    })
+}
+
+fn a_name(element: &XmlElement) -> Option<String> {
+    element.attr("name").and_then(|v| v.as_string())
+}
+
+/// Component names may be relative to the package (`.MainActivity`).
+fn qualify(package: &str, name: &str) -> String {
+    if name.starts_with('.') {
+        format!("{}{}", package, name)
+    } else if !name.contains('.') {
+        format!("{}.{}", package, name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Resolves `@string/...` and `@mipmap/...` style values through the
+/// resource table. Literal strings are returned as-is.
+pub(crate) fn resolve_label(
+    value: Option<&AttrValue>,
+    table: Option<&ResourceTable>,
+) -> Option<String> {
+    match value? {
+        AttrValue::Reference(id) => table?.resolve_string(*id),
+        other => other.as_string(),
+    }
+}
+
+/// Picks the densest raster rendition of the icon. Adaptive icons
+/// (`mipmap-anydpi-v26/*.xml`) cannot be rasterised here, so when there is no
+/// raster fallback only the XML path is returned, flagged as not raster.
+pub(crate) fn choose_icon_path(
+    table: Option<&ResourceTable>,
+    icon: Option<&AttrValue>,
+) -> Option<(String, bool)> {
+    let files = match (icon, table) {
+        (Some(AttrValue::Reference(id)), Some(table)) => table.resolve_files(*id),
+        (Some(AttrValue::String(path)), _) => vec![(0, path.clone())],
+        _ => return None,
+    };
+
+    match files
+        .iter()
+        .map(|(_, path)| path)
+        .find(|path| !path.ends_with(".xml"))
+    {
+        Some(path) => Some((path.clone(), true)),
+        None => files.first().map(|(_, path)| (path.clone(), false)),
+    }
+}
+
+pub(crate) fn pick_icon<R: Read + std::io::Seek>(
+    zip: &mut ZipArchive<R>,
+    table: Option<&ResourceTable>,
+    icon: Option<&AttrValue>,
+) -> (Option<String>, Option<Vec<u8>>) {
+    match choose_icon_path(table, icon) {
+        Some((path, true)) => {
+            let bytes = read_zip_entry(zip, &path);
+            (Some(path), bytes)
+        }
+        Some((path, false)) => (Some(path), None),
+        None => (None, None),
+    }
+}
+
+fn compare_versions(apk_code: &str, installed_code: &str) -> VersionComparison {
+    match (apk_code.parse::<u64>(), installed_code.parse::<u64>()) {
+        (Ok(apk), Ok(installed)) if apk > installed => VersionComparison::Upgrade,
+        (Ok(apk), Ok(installed)) if apk < installed => VersionComparison::Downgrade,
+        (Ok(_), Ok(_)) => VersionComparison::Same,
+        _ => VersionComparison::Unknown,
+    }
+}
+
+pub(crate) fn read_apk_info(path: &str) -> Result<ApkInfo, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open APK: {}", e))?;
+    let file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a valid APK: {}", e))?;
+
+    let manifest_bytes =
+        read_zip_entry(&mut zip, "AndroidManifest.xml").ok_or("APK has no AndroidManifest.xml")?;
+    let manifest = parse_xml(&manifest_bytes)?;
+    let table = read_zip_entry(&mut zip, "resources.arsc")
+        .and_then(|bytes| ResourceTable::parse(&bytes).ok());
+
+    let package_id = manifest
+        .attr("package")
+        .and_then(|v| v.as_string())
+        .unwrap_or_default();
+    let version_code = manifest
+        .attr("versionCode")
+        .and_then(|v| v.as_int())
+        .map(|code| (code as u32 as u64).to_string())
+        .unwrap_or_default();
+    let version_name =
+        resolve_label(manifest.attr("versionName"), table.as_ref()).unwrap_or_default();
+
+    let uses_sdk = manifest.children_named("uses-sdk").next();
+    let min_sdk = uses_sdk
+        .and_then(|sdk| sdk.attr("minSdkVersion"))
+        .and_then(|v| v.as_int());
+    let target_sdk = uses_sdk
+        .and_then(|sdk| sdk.attr("targetSdkVersion"))
+        .and_then(|v| v.as_int());
+
+    let permissions = manifest
+        .children
+        .iter()
+        .filter(|c| c.name.starts_with("uses-permission"))
+        .filter_map(a_name)
+        .collect();
+
+    let application = manifest.children_named("application").next();
+    let label = application.and_then(|a| resolve_label(a.attr("label"), table.as_ref()));
+    let (icon_path, icon_bytes) = match application {
+        Some(app) => pick_icon(&mut zip, table.as_ref(), app.attr("icon")),
+        None => (None, None),
+    };
+
+    let mut components = Vec::new();
+    if let Some(application) = application {
+        for child in &application.children {
+            if !COMPONENT_TAGS.contains(&child.name.as_str()) {
+                continue;
+            }
+            components.push(ApkComponent {
+                kind: child.name.clone(),
+                name: qualify(&package_id, &a_name(child).unwrap_or_default()),
+                exported: child.attr("exported").and_then(|v| v.as_bool()),
+                is_launcher: has_launcher_filter(child),
+            });
+        }
+    }
+
+    let mut native_abis: Vec<String> = zip
+        .file_names()
+        .filter_map(|name| name.strip_prefix("lib/"))
+        .filter_map(|rest| rest.split_once('/').map(|(abi, _)| abi.to_string()))
+        .collect();
+    native_abis.sort();
+    native_abis.dedup();
+
+    Ok(ApkInfo {
+        package_id,
+        version_name,
+        version_code,
+        min_sdk,
+        target_sdk,
+        label,
+        icon: icon_bytes.map(|bytes| BASE64.encode(bytes)),
+        icon_path,
+        permissions,
+        components,
+        native_abis,
+        file_size,
+        installed: None,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Decodes a local APK's manifest without installing it. With a `device`,
+/// the result also says how it compares to the version installed there.
+#[tauri::command]
+pub async fn inspect_apk(
+    app: AppHandle,
+    path: String,
+    device: Option<String>,
+) -> Result<ApkInfo, String> {
+    let mut info = read_apk_info(&path)?;
+
+    if let Some(device) = device {
+        let adb_path = resolve_adb_path(&app);
+        info.installed = Some(
+            match fetch_package_details(&adb_path, &device, &info.package_id, None) {
+                Ok(details) if !details.version_code.is_empty() => InstalledVersion {
+                    comparison: compare_versions(&info.version_code, &details.version_code),
+                    version_name: details.version_name,
+                    version_code: details.version_code,
+                },
+                _ => InstalledVersion {
+                    version_name: String::new(),
+                    version_code: String::new(),
+                    comparison: VersionComparison::NotInstalled,
+                },
+            },
+        );
+    }
+
+    Ok(info)
+}
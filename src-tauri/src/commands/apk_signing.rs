@@ -0,0 +1,583 @@
+use crate::commands::apk_info::{read_apk_info, read_zip_entry};
+use crate::commands::utils::{resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use zip::ZipArchive;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SigningCertificate {
+    pub sha256: String,
+    pub subject: String,
+    pub issuer: String,
+    pub serial_number: String,
+    pub valid_from: String,
+    pub valid_until: String,
+    pub expired: bool,
+    pub key_algorithm: String,
+    /// `Signature.hashCode()` in hex, which is what `dumpsys package` prints.
+    pub hash_code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignatureScheme {
+    pub scheme: String, // "v1", "v2", "v3", "v3.1"
+    pub certificates: Vec<SigningCertificate>,
+    pub min_sdk: Option<u32>,
+    pub max_sdk: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InstalledSignatureCheck {
+    pub installed: bool,
+    pub signatures: Vec<String>,
+    /// Keys the installed package rotated away from; informational only, an
+    /// APK signed with one of them is still rejected.
+    pub past_signatures: Vec<String>,
+    /// The APK is signed with the installed package's current signer.
+    pub matches: bool,
+    /// True when installing this APK over the current one would fail with
+    /// `INSTALL_FAILED_UPDATE_INCOMPATIBLE`.
+    pub update_incompatible: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApkSignatures {
+    pub schemes: Vec<SignatureScheme>,
+    pub installed: Option<InstalledSignatureCheck>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const EOCD_MAGIC: u32 = 0x0605_4b50;
+const SIG_BLOCK_MAGIC: &[u8; 16] = b"APK Sig Block 42";
+const V2_BLOCK_ID: u32 = 0x7109_871a;
+const V3_BLOCK_ID: u32 = 0xf053_68c0;
+const V31_BLOCK_ID: u32 = 0x1b93_ad61;
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(bytes);
+    Some(u64::from_le_bytes(buf))
+}
+
+/// Splits a sequence of `u32`-length-prefixed values.
+fn length_prefixed(data: &[u8]) -> Vec<&[u8]> {
+    let mut items = Vec::new();
+    let mut offset = 0;
+    while let Some(len) = read_u32(data, offset) {
+        let start = offset + 4;
+        match data.get(start..start + len as usize) {
+            Some(item) => items.push(item),
+            None => break,
+        }
+        offset = start + len as usize;
+    }
+    items
+}
+
+/// Reads a single `u32`-length-prefixed value at `offset`.
+fn prefixed_at(data: &[u8], offset: usize) -> Option<&[u8]> {
+    let len = read_u32(data, offset)? as usize;
+    data.get(offset + 4..offset + 4 + len)
+}
+
+/// Locates the APK Signing Block, which sits right before the central
+/// directory, and returns its `(id, value)` pairs.
+fn read_signing_block(file: &mut File) -> Result<Vec<(u32, Vec<u8>)>, String> {
+    let len = file.metadata().map_err(|e| e.to_string())?.len();
+    let tail_len = len.min(65_535 + 22);
+    let mut tail = vec![0u8; tail_len as usize];
+    file.seek(SeekFrom::Start(len - tail_len))
+        .and_then(|_| file.read_exact(&mut tail))
+        .map_err(|e| e.to_string())?;
+
+    let eocd = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|&i| read_u32(&tail, i) == Some(EOCD_MAGIC))
+        .ok_or("Not a zip archive")?;
+    let cd_offset = read_u32(&tail, eocd + 16).ok_or("Truncated zip")? as u64;
+    if cd_offset < 32 {
+        return Ok(Vec::new());
+    }
+
+    let mut footer = [0u8; 24];
+    file.seek(SeekFrom::Start(cd_offset - 24))
+        .and_then(|_| file.read_exact(&mut footer))
+        .map_err(|e| e.to_string())?;
+    if &footer[8..] != SIG_BLOCK_MAGIC {
+        return Ok(Vec::new());
+    }
+
+    let block_size = read_u64(&footer, 0).unwrap_or(0);
+    if block_size < 24 || block_size + 8 > cd_offset || block_size > 64 * 1024 * 1024 {
+        return Err("Corrupt APK Signing Block".to_string());
+    }
+    let mut block = vec![0u8; block_size as usize - 24];
+    file.seek(SeekFrom::Start(cd_offset - block_size))
+        .and_then(|_| file.read_exact(&mut block))
+        .map_err(|e| e.to_string())?;
+
+    let mut pairs = Vec::new();
+    let mut offset = 0;
+    while let Some(pair_len) = read_u64(&block, offset) {
+        let start = offset + 8;
+        let end = start.saturating_add(pair_len as usize);
+        let (Some(id), Some(value)) = (read_u32(&block, start), block.get(start + 4..end)) else {
+            break;
+        };
+        pairs.push((id, value.to_vec()));
+        offset = end;
+    }
+    Ok(pairs)
+}
+
+struct Signer {
+    certificates: Vec<Vec<u8>>,
+    sdk_range: Option<(u32, u32)>,
+}
+
+/// Certificates and SDK range of each signer in a v2/v3/v3.1 block.
+/// Signed data starts with digests and certificates in all three schemes;
+/// v3 signers carry their min/max SDK right after it.
+fn parse_signers(value: &[u8], has_sdk_range: bool) -> Vec<Signer> {
+    let mut signers = Vec::new();
+    let Some(sequence) = prefixed_at(value, 0) else {
+        return signers;
+    };
+    for signer in length_prefixed(sequence) {
+        let Some(signed_data) = prefixed_at(signer, 0) else {
+            continue;
+        };
+        let digests_len = read_u32(signed_data, 0).unwrap_or(0) as usize;
+        let certificates = prefixed_at(signed_data, 4 + digests_len)
+            .map(|certs| length_prefixed(certs).iter().map(|c| c.to_vec()).collect())
+            .unwrap_or_default();
+        let sdk_range = if has_sdk_range {
+            let after = 4 + signed_data.len();
+            read_u32(signer, after).zip(read_u32(signer, after + 4))
+        } else {
+            None
+        };
+        signers.push(Signer {
+            certificates,
+            sdk_range,
+        });
+    }
+    signers
+}
+
+// --- Minimal DER reader, enough for X.509 certificates and PKCS#7 -------
+
+struct Der<'a> {
+    tag: u8,
+    content: &'a [u8],
+    /// The full TLV encoding.
+    raw: &'a [u8],
+}
+
+fn der_read(data: &[u8]) -> Option<(Der<'_>, &[u8])> {
+    let tag = *data.first()?;
+    let first = *data.get(1)? as usize;
+    let (len, header) = if first & 0x80 == 0 {
+        (first, 2)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let len = data
+            .get(2..2 + count)?
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, 2 + count)
+    };
+    let end = header.checked_add(len)?;
+    let raw = data.get(..end)?;
+    Some((
+        Der {
+            tag,
+            content: &raw[header..],
+            raw,
+        },
+        &data[end..],
+    ))
+}
+
+fn der_children(data: &[u8]) -> Vec<Der<'_>> {
+    let mut children = Vec::new();
+    let mut rest = data;
+    while let Some((child, next)) = der_read(rest) {
+        children.push(child);
+        rest = next;
+    }
+    children
+}
+
+fn oid_to_string(bytes: &[u8]) -> String {
+    let mut parts = Vec::new();
+    if let Some(first) = bytes.first() {
+        parts.push((first / 40).to_string());
+        parts.push((first % 40).to_string());
+    }
+    let mut value: u64 = 0;
+    for b in bytes.iter().skip(1) {
+        value = (value << 7) | (*b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            parts.push(value.to_string());
+            value = 0;
+        }
+    }
+    parts.join(".")
+}
+
+fn name_to_string(name: &Der) -> String {
+    let mut parts = Vec::new();
+    for rdn in der_children(name.content) {
+        for attribute in der_children(rdn.content) {
+            let fields = der_children(attribute.content);
+            let (Some(oid), Some(value)) = (fields.first(), fields.get(1)) else {
+                continue;
+            };
+            let key = match oid_to_string(oid.content).as_str() {
+                "2.5.4.3" => "CN".to_string(),
+                "2.5.4.6" => "C".to_string(),
+                "2.5.4.7" => "L".to_string(),
+                "2.5.4.8" => "ST".to_string(),
+                "2.5.4.10" => "O".to_string(),
+                "2.5.4.11" => "OU".to_string(),
+                "1.2.840.113549.1.9.1" => "E".to_string(),
+                other => other.to_string(),
+            };
+            let text = if value.tag == 0x1e {
+                // BMPString
+                let units: Vec<u16> = value
+                    .content
+                    .chunks(2)
+                    .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            } else {
+                String::from_utf8_lossy(value.content).to_string()
+            };
+            parts.push(format!("{}={}", key, text));
+        }
+    }
+    parts.join(", ")
+}
+
+/// UTCTime (`YYMMDDHHMMSSZ`) or GeneralizedTime (`YYYYMMDDHHMMSSZ`) to ISO 8601.
+fn der_time(time: &Der) -> String {
+    let text = String::from_utf8_lossy(time.content).to_string();
+    let digits = text.trim_end_matches('Z');
+    if !digits.is_ascii() {
+        return text;
+    }
+    let full = if time.tag == 0x17 && digits.len() >= 12 {
+        let year: u32 = digits[..2].parse().unwrap_or(0);
+        let century = if year >= 50 { "19" } else { "20" };
+        format!("{}{}", century, digits)
+    } else {
+        digits.to_string()
+    };
+    if full.len() < 14 {
+        return text;
+    }
+    format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &full[0..4],
+        &full[4..6],
+        &full[6..8],
+        &full[8..10],
+        &full[10..12],
+        &full[12..14]
+    )
+}
+
+/// Current UTC time in the same ISO 8601 form as `der_time`.
+fn now_iso() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil-from-days (Howard Hinnant)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Java's `Arrays.hashCode(byte[])`, as used by `android.content.pm.Signature`.
+fn java_hash_code(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(1i32, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(*b as i8 as i32)
+    });
+    format!("{:x}", hash as u32)
+}
+
+fn parse_certificate(der: &[u8]) -> Option<SigningCertificate> {
+    let (certificate, _) = der_read(der)?;
+    let (tbs, _) = der_read(certificate.content)?;
+    let mut fields = der_children(tbs.content).into_iter().peekable();
+
+    // Optional explicit [0] version
+    if fields.peek().map(|f| f.tag) == Some(0xa0) {
+        fields.next();
+    }
+    let serial = fields.next()?;
+    let _signature_algorithm = fields.next()?;
+    let issuer = fields.next()?;
+    let validity = fields.next()?;
+    let subject = fields.next()?;
+    let public_key = fields.next()?;
+
+    let times = der_children(validity.content);
+    let valid_from = times.first().map(der_time).unwrap_or_default();
+    let valid_until = times.get(1).map(der_time).unwrap_or_default();
+
+    let key_algorithm = der_children(public_key.content)
+        .first()
+        .and_then(|algorithm| der_children(algorithm.content).into_iter().next())
+        .map(|oid| match oid_to_string(oid.content).as_str() {
+            "1.2.840.113549.1.1.1" => "RSA".to_string(),
+            "1.2.840.10045.2.1" => "EC".to_string(),
+            "1.2.840.10040.4.1" => "DSA".to_string(),
+            other => other.to_string(),
+        })
+        .unwrap_or_default();
+
+    Some(SigningCertificate {
+        sha256: hex(&Sha256::digest(certificate.raw)),
+        subject: name_to_string(&subject),
+        issuer: name_to_string(&issuer),
+        // DER pads positive serials with a leading zero byte
+        serial_number: hex(match serial.content {
+            [0, rest @ ..] if !rest.is_empty() => rest,
+            all => all,
+        }),
+        expired: !valid_until.is_empty() && valid_until < now_iso(),
+        valid_from,
+        valid_until,
+        key_algorithm,
+        hash_code: java_hash_code(certificate.raw),
+    })
+}
+
+/// Pulls the certificate set out of a PKCS#7 `SignedData` blob
+/// (`META-INF/*.RSA`, `*.DSA`, `*.EC`).
+fn pkcs7_certificates(data: &[u8]) -> Vec<Vec<u8>> {
+    let Some((content_info, _)) = der_read(data) else {
+        return Vec::new();
+    };
+    let Some(explicit) = der_children(content_info.content)
+        .into_iter()
+        .find(|c| c.tag == 0xa0)
+    else {
+        return Vec::new();
+    };
+    let Some((signed_data, _)) = der_read(explicit.content) else {
+        return Vec::new();
+    };
+    der_children(signed_data.content)
+        .into_iter()
+        .find(|c| c.tag == 0xa0)
+        .map(|certs| {
+            der_children(certs.content)
+                .into_iter()
+                .map(|c| c.raw.to_vec())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn v1_certificates(path: &str) -> Result<Vec<Vec<u8>>, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open APK: {}", e))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| format!("Not a valid APK: {}", e))?;
+    let names: Vec<String> = zip
+        .file_names()
+        .filter(|name| {
+            let upper = name.to_uppercase();
+            upper.starts_with("META-INF/")
+                && !upper["META-INF/".len()..].contains('/')
+                && (upper.ends_with(".RSA") || upper.ends_with(".DSA") || upper.ends_with(".EC"))
+        })
+        .map(|name| name.to_string())
+        .collect();
+
+    let mut certificates = Vec::new();
+    for name in names {
+        // Signature blocks are a few KB; read_zip_entry caps oversized ones
+        let bytes = read_zip_entry(&mut zip, &name)
+            .ok_or_else(|| format!("Failed to read signature file {}", name))?;
+        certificates.extend(pkcs7_certificates(&bytes));
+    }
+    Ok(certificates)
+}
+
+pub(crate) fn read_apk_signatures(path: &str) -> Result<Vec<SignatureScheme>, String> {
+    let mut schemes = Vec::new();
+
+    let v1 = v1_certificates(path)?;
+    if !v1.is_empty() {
+        schemes.push(SignatureScheme {
+            scheme: "v1".to_string(),
+            certificates: v1.iter().filter_map(|c| parse_certificate(c)).collect(),
+            min_sdk: None,
+            max_sdk: None,
+        });
+    }
+
+    let mut file = File::open(path).map_err(|e| format!("Failed to open APK: {}", e))?;
+    for (id, value) in read_signing_block(&mut file)? {
+        let (scheme, has_sdk_range) = match id {
+            V2_BLOCK_ID => ("v2", false),
+            V3_BLOCK_ID => ("v3", true),
+            V31_BLOCK_ID => ("v3.1", true),
+            _ => continue,
+        };
+        for signer in parse_signers(&value, has_sdk_range) {
+            schemes.push(SignatureScheme {
+                scheme: scheme.to_string(),
+                certificates: signer
+                    .certificates
+                    .iter()
+                    .filter_map(|c| parse_certificate(c))
+                    .collect(),
+                min_sdk: signer.sdk_range.map(|(min, _)| min),
+                max_sdk: signer.sdk_range.map(|(_, max)| max),
+            });
+        }
+    }
+
+    if schemes.is_empty() {
+        return Err("APK is not signed".to_string());
+    }
+    Ok(schemes)
+}
+
+/// Parses `signatures=PackageSignatures{9fbbf8a version:3, signatures:[5b6b2b1a],
+/// past signatures:[1f2e3d4c, 5b6b2b1a flags: 17]}` from `dumpsys package`.
+fn parse_installed_signatures(dump: &str) -> Option<(Vec<String>, Vec<String>)> {
+    let line = dump
+        .lines()
+        .map(|l| l.trim())
+        .find(|l| l.starts_with("signatures=PackageSignatures"))?;
+
+    let list = |key: &str| -> Vec<String> {
+        line.find(key)
+            .and_then(|idx| {
+                let rest = &line[idx + key.len()..];
+                rest.split(']').next()
+            })
+            .map(|inner| {
+                inner
+                    .split(',')
+                    .filter_map(|item| item.split_whitespace().next())
+                    .filter(|item| item.chars().all(|c| c.is_ascii_hexdigit()))
+                    .map(|item| item.to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Some((list(" signatures:["), list("past signatures:[")))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Reads the signing certificates of a local APK. With a `device`, they are
+/// compared against the installed package so an incompatible update can be
+/// flagged before `install_package` runs.
+#[tauri::command]
+pub async fn verify_apk_signature(
+    app: AppHandle,
+    path: String,
+    device: Option<String>,
+) -> Result<ApkSignatures, String> {
+    let schemes = read_apk_signatures(&path)?;
+
+    let installed = match device {
+        Some(device) => {
+            let package = read_apk_info(&path)?.package_id;
+            let adb_path = resolve_adb_path(&app);
+            let output = Command::new(&adb_path)
+                .args([
+                    "-s",
+                    &device,
+                    "shell",
+                    "dumpsys",
+                    "package",
+                    &shell_quote(&package),
+                ])
+                .output()
+                .map_err(|e| format!("Failed to execute adb: {}", e))?;
+            let dump = String::from_utf8_lossy(&output.stdout);
+
+            Some(match parse_installed_signatures(&dump) {
+                Some((signatures, past_signatures)) => {
+                    let local: Vec<&str> = schemes
+                        .iter()
+                        .flat_map(|s| s.certificates.iter())
+                        .map(|c| c.hash_code.as_str())
+                        .collect();
+                    let matches = signatures.iter().any(|sig| local.contains(&sig.as_str()));
+                    InstalledSignatureCheck {
+                        installed: true,
+                        update_incompatible: !matches,
+                        signatures,
+                        past_signatures,
+                        matches,
+                    }
+                }
+                None => InstalledSignatureCheck {
+                    installed: false,
+                    signatures: Vec::new(),
+                    past_signatures: Vec::new(),
+                    matches: false,
+                    update_incompatible: false,
+                },
+            })
+        }
+        None => None,
+    };
+
+    Ok(ApkSignatures { schemes, installed })
+}
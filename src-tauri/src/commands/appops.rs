@@ -0,0 +1,270 @@
+use crate::commands::packages::user_args;
+use crate::commands::utils::{resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppOp {
+    /// Op name as `appops` prints it (`RUN_IN_BACKGROUND`, `SYSTEM_ALERT_WINDOW`).
+    pub name: String,
+    /// `allow`, `ignore`, `deny`, `default` or `foreground`.
+    pub mode: String,
+    /// True for ops set on the whole UID rather than the package.
+    pub is_uid_mode: bool,
+    /// Last access, as printed by the device (absolute or relative).
+    pub last_access: Option<String>,
+    pub last_access_ms_ago: Option<u64>,
+    pub last_reject: Option<String>,
+    pub last_reject_ms_ago: Option<u64>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const APP_OP_MODES: &[&str] = &["allow", "ignore", "deny", "default", "foreground"];
+
+pub(crate) fn run_appops(adb_path: &str, device: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell", "cmd", "appops"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || stderr.contains("Error") || stdout.starts_with("Error") {
+        return Err(format!("{}{}", stdout, stderr).trim().to_string());
+    }
+    Ok(stdout)
+}
+
+/// Parses durations such as `+1d2h3m4s5ms` or `-1h2m3s4ms` into milliseconds.
+fn parse_duration_ms(value: &str) -> Option<u64> {
+    let value = value.trim().trim_start_matches(['+', '-']);
+    let mut total = 0u64;
+    let mut number = String::new();
+    let mut chars = value.chars().peekable();
+    let mut parsed_any = false;
+
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: u64 = number.parse().ok()?;
+        number.clear();
+        let unit = match c {
+            'd' => 86_400_000,
+            'h' => 3_600_000,
+            's' => 1_000,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                1
+            }
+            'm' => 60_000,
+            _ => return None,
+        };
+        total += amount * unit;
+        parsed_any = true;
+    }
+
+    if parsed_any && number.is_empty() {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+/// Splits the timestamp part of an access line into the printed value and,
+/// where possible, how long ago it was.
+///
+/// Old format: `time=+1d2h3m ago` -> value `+1d2h3m ago`.
+/// New format: `[fg-s] 2024-05-01 12:00:00.123 (-1h2m3s4ms)`.
+fn parse_access(value: &str) -> (String, Option<u64>) {
+    let value = value.trim();
+    if let Some(relative) = value
+        .rsplit_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+    {
+        return (value.to_string(), parse_duration_ms(relative));
+    }
+    let ago = value.strip_suffix(" ago").and_then(parse_duration_ms);
+    (value.to_string(), ago)
+}
+
+/// Parses `cmd appops get <package>`. Android 10 and older print everything on
+/// one line per op:
+///
+/// ```text
+/// Uid mode: COARSE_LOCATION: foreground
+/// RUN_IN_BACKGROUND: allow; time=+1d2h3m ago; duration=+12ms
+/// ```
+///
+/// Android 11+ prints accesses in an indented block below each op:
+///
+/// ```text
+/// COARSE_LOCATION: allow
+///     null=[
+///       Access: [fg-s] 2024-05-01 12:00:00.123 (-1h2m3s4ms)
+///     ]
+/// ```
+fn parse_app_ops(output: &str) -> Vec<AppOp> {
+    let mut ops: Vec<AppOp> = Vec::new();
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if indented {
+            // Access/reject details of the previous op
+            let Some(op) = ops.last_mut() else {
+                continue;
+            };
+            if let Some(rest) = trimmed.strip_prefix("Access:") {
+                let (value, ago) = parse_access(rest);
+                if op
+                    .last_access_ms_ago
+                    .is_none_or(|prev| ago.is_some_and(|a| a < prev))
+                {
+                    op.last_access = Some(value);
+                    op.last_access_ms_ago = ago;
+                }
+            } else if let Some(rest) = trimmed.strip_prefix("Reject:") {
+                let (value, ago) = parse_access(rest);
+                if op
+                    .last_reject_ms_ago
+                    .is_none_or(|prev| ago.is_some_and(|a| a < prev))
+                {
+                    op.last_reject = Some(value);
+                    op.last_reject_ms_ago = ago;
+                }
+            }
+            continue;
+        }
+
+        let (is_uid_mode, rest) = match trimmed.strip_prefix("Uid mode: ") {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let Some((name, details)) = rest.split_once(": ") else {
+            continue;
+        };
+        if name.contains(' ') {
+            continue;
+        }
+
+        let mut parts = details.split(';').map(|p| p.trim());
+        let mode = parts.next().unwrap_or_default().to_string();
+        let mut op = AppOp {
+            name: name.to_string(),
+            mode,
+            is_uid_mode,
+            last_access: None,
+            last_access_ms_ago: None,
+            last_reject: None,
+            last_reject_ms_ago: None,
+        };
+        for part in parts {
+            if let Some(time) = part.strip_prefix("time=") {
+                let (value, ago) = parse_access(time);
+                op.last_access = Some(value);
+                op.last_access_ms_ago = ago;
+            } else if let Some(time) = part.strip_prefix("rejectTime=") {
+                let (value, ago) = parse_access(time);
+                op.last_reject = Some(value);
+                op.last_reject_ms_ago = ago;
+            }
+        }
+        ops.push(op);
+    }
+
+    ops
+}
+
+pub(crate) fn fetch_app_ops(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    user_id: Option<u32>,
+) -> Result<Vec<AppOp>, String> {
+    let mut args = vec!["get".to_string()];
+    args.extend(user_args(user_id));
+    args.push(shell_quote(package));
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    let output = run_appops(adb_path, device, &args)?;
+    Ok(parse_app_ops(&output))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_app_ops(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<Vec<AppOp>, String> {
+    let adb_path = resolve_adb_path(&app);
+    fetch_app_ops(&adb_path, &device, &package, user_id)
+}
+
+/// `appops set [--user N] [--uid] <package> <op> <mode>`. With `uid` set the
+/// mode applies to every package sharing the UID. Returns the updated ops.
+#[tauri::command]
+pub async fn set_app_op(
+    app: AppHandle,
+    device: String,
+    package: String,
+    op: String,
+    mode: String,
+    uid: bool,
+    user_id: Option<u32>,
+) -> Result<Vec<AppOp>, String> {
+    if !APP_OP_MODES.contains(&mode.as_str()) {
+        return Err(format!("Invalid app op mode: {}", mode));
+    }
+    if op.is_empty() || !op.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("Invalid app op: {}", op));
+    }
+
+    let adb_path = resolve_adb_path(&app);
+    let mut args = vec!["set".to_string()];
+    args.extend(user_args(user_id));
+    if uid {
+        args.push("--uid".to_string());
+    }
+    args.extend([shell_quote(&package), op, mode]);
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    run_appops(&adb_path, &device, &args)?;
+
+    fetch_app_ops(&adb_path, &device, &package, user_id)
+}
+
+/// `appops reset [--user N] <package>`: every op goes back to its default.
+#[tauri::command]
+pub async fn reset_app_ops(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<Vec<AppOp>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let mut args = vec!["reset".to_string()];
+    args.extend(user_args(user_id));
+    args.push(shell_quote(&package));
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    run_appops(&adb_path, &device, &args)?;
+
+    fetch_app_ops(&adb_path, &device, &package, user_id)
+}
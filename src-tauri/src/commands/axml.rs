@@ -0,0 +1,488 @@
+//! Decoders for Android's compiled resource formats: binary XML (AXML) as
+//! used by `AndroidManifest.xml`, and the `resources.arsc` resource table.
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttrValue {
+    String(String),
+    Reference(u32),
+    Int(i64),
+    Bool(bool),
+    Other(String),
+}
+
+impl AttrValue {
+    pub fn as_string(&self) -> Option<String> {
+        match self {
+            AttrValue::String(s) | AttrValue::Other(s) => Some(s.clone()),
+            AttrValue::Int(i) => Some(i.to_string()),
+            AttrValue::Bool(b) => Some(b.to_string()),
+            AttrValue::Reference(id) => Some(format!("@0x{:08x}", id)),
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            AttrValue::Int(i) => Some(*i),
+            AttrValue::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            AttrValue::Bool(b) => Some(*b),
+            AttrValue::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct XmlAttribute {
+    pub name: String,
+    pub resource_id: Option<u32>,
+    pub value: AttrValue,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<XmlAttribute>,
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Looks an attribute up by name, falling back to its framework
+    /// resource id for manifests whose attribute names were stripped.
+    pub fn attr(&self, name: &str) -> Option<&AttrValue> {
+        let id = framework_attr_id(name);
+        self.attributes
+            .iter()
+            .find(|a| a.name == name)
+            .or_else(|| {
+                id.and_then(|id| self.attributes.iter().find(|a| a.resource_id == Some(id)))
+            })
+            .map(|a| &a.value)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+}
+
+/// A value of a resource in one configuration of `resources.arsc`.
+#[derive(Debug, Clone)]
+pub struct ResourceValue {
+    pub language: String,
+    pub density: u16,
+    pub value: AttrValue,
+}
+
+#[derive(Debug, Default)]
+pub struct ResourceTable {
+    /// Keyed by full resource id (0xPPTTEEEE).
+    entries: std::collections::HashMap<u32, Vec<ResourceValue>>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const RES_STRING_POOL_TYPE: u16 = 0x0001;
+const RES_TABLE_TYPE: u16 = 0x0002;
+const RES_XML_TYPE: u16 = 0x0003;
+const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+const RES_XML_END_ELEMENT_TYPE: u16 = 0x0103;
+const RES_XML_RESOURCE_MAP_TYPE: u16 = 0x0180;
+const RES_TABLE_PACKAGE_TYPE: u16 = 0x0200;
+const RES_TABLE_TYPE_TYPE: u16 = 0x0201;
+
+const UTF8_FLAG: u32 = 0x100;
+const NO_ENTRY: u32 = 0xFFFF_FFFF;
+
+const TYPE_NULL: u8 = 0x00;
+const TYPE_REFERENCE: u8 = 0x01;
+const TYPE_STRING: u8 = 0x03;
+const TYPE_FLOAT: u8 = 0x04;
+const TYPE_INT_DEC: u8 = 0x10;
+const TYPE_INT_HEX: u8 = 0x11;
+const TYPE_INT_BOOLEAN: u8 = 0x12;
+
+fn framework_attr_id(name: &str) -> Option<u32> {
+    let id = match name {
+        "label" => 0x0101_0001,
+        "icon" => 0x0101_0002,
+        "name" => 0x0101_0003,
+        "permission" => 0x0101_0006,
+        "exported" => 0x0101_0010,
+        "versionCode" => 0x0101_021b,
+        "versionName" => 0x0101_021c,
+        "minSdkVersion" => 0x0101_020c,
+        "targetSdkVersion" => 0x0101_0270,
+        "maxSdkVersion" => 0x0101_0271,
+        "roundIcon" => 0x0101_052c,
+        "foreground" => 0x0101_0109,
+        "drawable" => 0x0101_0199,
+        _ => return None,
+    };
+    Some(id)
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Option<u8> {
+    data.get(offset).copied()
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+struct ChunkHeader {
+    kind: u16,
+    header_size: usize,
+    size: usize,
+}
+
+fn read_chunk(data: &[u8], offset: usize) -> Option<ChunkHeader> {
+    let header = ChunkHeader {
+        kind: read_u16(data, offset)?,
+        header_size: read_u16(data, offset + 2)? as usize,
+        size: read_u32(data, offset + 4)? as usize,
+    };
+    if header.size < 8 || offset + header.size > data.len() {
+        return None;
+    }
+    Some(header)
+}
+
+fn parse_string_pool(data: &[u8], offset: usize) -> Option<Vec<String>> {
+    let chunk = read_chunk(data, offset)?;
+    let count = read_u32(data, offset + 8)? as usize;
+    let flags = read_u32(data, offset + 16)?;
+    let strings_start = read_u32(data, offset + 20)? as usize;
+    let utf8 = flags & UTF8_FLAG != 0;
+    // Each string has a 4-byte offset in the chunk; larger counts are corrupt
+    if count > chunk.size.checked_sub(chunk.header_size)? / 4 {
+        return None;
+    }
+
+    let mut strings = Vec::with_capacity(count);
+    for i in 0..count {
+        let string_offset = read_u32(data, offset + chunk.header_size + i * 4)? as usize;
+        let start = offset + strings_start + string_offset;
+        let value = if utf8 {
+            read_utf8_string(data, start)
+        } else {
+            read_utf16_string(data, start)
+        };
+        strings.push(value.unwrap_or_default());
+    }
+    Some(strings)
+}
+
+fn read_utf8_string(data: &[u8], mut offset: usize) -> Option<String> {
+    // UTF-16 length first (skipped), then UTF-8 byte length; each takes
+    // two bytes when the high bit is set.
+    let skip = if read_u8(data, offset)? & 0x80 != 0 {
+        2
+    } else {
+        1
+    };
+    offset += skip;
+    let mut len = read_u8(data, offset)? as usize;
+    if len & 0x80 != 0 {
+        len = ((len & 0x7f) << 8) | read_u8(data, offset + 1)? as usize;
+        offset += 2;
+    } else {
+        offset += 1;
+    }
+    let bytes = data.get(offset..offset + len)?;
+    Some(String::from_utf8_lossy(bytes).to_string())
+}
+
+fn read_utf16_string(data: &[u8], mut offset: usize) -> Option<String> {
+    let mut len = read_u16(data, offset)? as usize;
+    if len & 0x8000 != 0 {
+        len = ((len & 0x7fff) << 16) | read_u16(data, offset + 2)? as usize;
+        offset += 4;
+    } else {
+        offset += 2;
+    }
+    let units: Vec<u16> = (0..len)
+        .map(|i| read_u16(data, offset + i * 2))
+        .collect::<Option<_>>()?;
+    Some(String::from_utf16_lossy(&units))
+}
+
+fn typed_value(strings: &[String], data_type: u8, data: u32) -> AttrValue {
+    match data_type {
+        TYPE_STRING => AttrValue::String(strings.get(data as usize).cloned().unwrap_or_default()),
+        TYPE_REFERENCE => AttrValue::Reference(data),
+        TYPE_INT_DEC | TYPE_INT_HEX => AttrValue::Int(data as i32 as i64),
+        TYPE_INT_BOOLEAN => AttrValue::Bool(data != 0),
+        TYPE_FLOAT => AttrValue::Other(f32::from_bits(data).to_string()),
+        TYPE_NULL => AttrValue::Other(String::new()),
+        _ => AttrValue::Other(format!("0x{:08x}", data)),
+    }
+}
+
+/// Decodes a binary XML document into its root element.
+pub fn parse_xml(data: &[u8]) -> Result<XmlElement, String> {
+    let root = read_chunk(data, 0).ok_or("Invalid binary XML")?;
+    if root.kind != RES_XML_TYPE {
+        return Err("Not a binary XML document".to_string());
+    }
+
+    let mut strings = Vec::new();
+    let mut resource_ids = Vec::new();
+    // Open elements; the bottom one collects finished top-level elements.
+    let mut stack = vec![XmlElement::default()];
+
+    let mut offset = root.header_size;
+    while let Some(chunk) = read_chunk(data, offset) {
+        match chunk.kind {
+            RES_STRING_POOL_TYPE => {
+                strings = parse_string_pool(data, offset).ok_or("Invalid string pool")?;
+            }
+            RES_XML_RESOURCE_MAP_TYPE => {
+                let count = chunk
+                    .size
+                    .checked_sub(chunk.header_size)
+                    .ok_or("Invalid resource map")?
+                    / 4;
+                resource_ids = (0..count)
+                    .filter_map(|i| read_u32(data, offset + chunk.header_size + i * 4))
+                    .collect();
+            }
+            RES_XML_START_ELEMENT_TYPE => {
+                let ext = offset + chunk.header_size;
+                let name = read_u32(data, ext + 4).ok_or("Truncated element")? as usize;
+                let attr_start = read_u16(data, ext + 8).ok_or("Truncated element")? as usize;
+                let attr_size = read_u16(data, ext + 10).ok_or("Truncated element")? as usize;
+                let attr_count = read_u16(data, ext + 12).ok_or("Truncated element")? as usize;
+
+                let mut element = XmlElement {
+                    name: strings.get(name).cloned().unwrap_or_default(),
+                    ..Default::default()
+                };
+                for i in 0..attr_count {
+                    let attr = ext + attr_start + i * attr_size;
+                    let (Some(name), Some(raw), Some(data_type), Some(value)) = (
+                        read_u32(data, attr + 4),
+                        read_u32(data, attr + 8),
+                        read_u8(data, attr + 15),
+                        read_u32(data, attr + 16),
+                    ) else {
+                        break;
+                    };
+                    let name = name as usize;
+                    let value = if raw != NO_ENTRY && data_type == TYPE_STRING {
+                        AttrValue::String(strings.get(raw as usize).cloned().unwrap_or_default())
+                    } else {
+                        typed_value(&strings, data_type, value)
+                    };
+                    element.attributes.push(XmlAttribute {
+                        name: strings.get(name).cloned().unwrap_or_default(),
+                        resource_id: resource_ids.get(name).copied(),
+                        value,
+                    });
+                }
+                stack.push(element);
+            }
+            RES_XML_END_ELEMENT_TYPE if stack.len() > 1 => {
+                let element = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(element);
+                }
+            }
+            _ => {}
+        }
+        offset += chunk.size;
+    }
+
+    stack
+        .into_iter()
+        .next()
+        .and_then(|document| document.children.into_iter().next())
+        .ok_or_else(|| "Binary XML has no root element".to_string())
+}
+
+impl ResourceTable {
+    /// Decodes `resources.arsc`. Only simple (non-bag) entries are kept,
+    /// which covers strings, references and file paths.
+    pub fn parse(data: &[u8]) -> Result<ResourceTable, String> {
+        let root = read_chunk(data, 0).ok_or("Invalid resource table")?;
+        if root.kind != RES_TABLE_TYPE {
+            return Err("Not a resource table".to_string());
+        }
+
+        let mut table = ResourceTable::default();
+        let mut global_strings = Vec::new();
+
+        let mut offset = root.header_size;
+        while let Some(chunk) = read_chunk(data, offset) {
+            match chunk.kind {
+                RES_STRING_POOL_TYPE => {
+                    global_strings =
+                        parse_string_pool(data, offset).ok_or("Invalid string pool")?;
+                }
+                RES_TABLE_PACKAGE_TYPE => {
+                    table.parse_package(data, offset, &chunk, &global_strings);
+                }
+                _ => {}
+            }
+            offset += chunk.size;
+        }
+        Ok(table)
+    }
+
+    fn parse_package(
+        &mut self,
+        data: &[u8],
+        start: usize,
+        package: &ChunkHeader,
+        strings: &[String],
+    ) {
+        let package_id = read_u32(data, start + 8).unwrap_or(0x7f) & 0xff;
+        let end = start + package.size;
+
+        let mut offset = start + package.header_size;
+        while offset < end {
+            let Some(chunk) = read_chunk(data, offset) else {
+                break;
+            };
+            if chunk.kind == RES_TABLE_TYPE_TYPE {
+                self.parse_type(data, offset, &chunk, package_id, strings);
+            }
+            offset += chunk.size;
+        }
+    }
+
+    fn parse_type(
+        &mut self,
+        data: &[u8],
+        start: usize,
+        chunk: &ChunkHeader,
+        package_id: u32,
+        strings: &[String],
+    ) -> Option<()> {
+        const FLAG_SPARSE: u8 = 0x01;
+        const FLAG_OFFSET16: u8 = 0x02;
+        const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+        const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+
+        let type_id = read_u8(data, start + 8)? as u32;
+        let flags = read_u8(data, start + 9)?;
+        let entry_count = read_u32(data, start + 12)? as usize;
+        let entries_start = read_u32(data, start + 16)? as usize;
+
+        // ResTable_config: language at +8, density at +14
+        let config = start + 20;
+        let language: String = data
+            .get(config + 8..config + 10)?
+            .iter()
+            .take_while(|b| **b != 0)
+            .map(|b| *b as char)
+            .collect();
+        let density = read_u16(data, config + 14)?;
+
+        let index_start = start + chunk.header_size;
+        let mut entries = Vec::new();
+        for i in 0..entry_count {
+            if flags & FLAG_SPARSE != 0 {
+                let idx = read_u16(data, index_start + i * 4)? as u32;
+                let off = read_u16(data, index_start + i * 4 + 2)? as usize * 4;
+                entries.push((idx, off));
+            } else if flags & FLAG_OFFSET16 != 0 {
+                let off = read_u16(data, index_start + i * 2)?;
+                if off != 0xffff {
+                    entries.push((i as u32, off as usize * 4));
+                }
+            } else {
+                let off = read_u32(data, index_start + i * 4)?;
+                if off != NO_ENTRY {
+                    entries.push((i as u32, off as usize));
+                }
+            }
+        }
+
+        for (index, entry_offset) in entries {
+            let entry = start + entries_start + entry_offset;
+            let entry_flags = read_u16(data, entry + 2)?;
+            let (data_type, value) = if entry_flags & ENTRY_FLAG_COMPACT != 0 {
+                ((entry_flags >> 8) as u8, read_u32(data, entry + 4)?)
+            } else if entry_flags & ENTRY_FLAG_COMPLEX != 0 {
+                continue;
+            } else {
+                let size = read_u16(data, entry)? as usize;
+                (
+                    read_u8(data, entry + size + 3)?,
+                    read_u32(data, entry + size + 4)?,
+                )
+            };
+
+            let id = (package_id << 24) | (type_id << 16) | index;
+            self.entries.entry(id).or_default().push(ResourceValue {
+                language: language.clone(),
+                density,
+                value: typed_value(strings, data_type, value),
+            });
+        }
+        Some(())
+    }
+
+    pub fn values(&self, id: u32) -> &[ResourceValue] {
+        self.entries.get(&id).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Resolves a reference to a display string, preferring the default
+    /// (language-less) configuration and following reference chains.
+    pub fn resolve_string(&self, id: u32) -> Option<String> {
+        let mut id = id;
+        for _ in 0..8 {
+            let values = self.values(id);
+            let value = values
+                .iter()
+                .find(|v| v.language.is_empty())
+                .or_else(|| values.first())?;
+            match &value.value {
+                AttrValue::Reference(next) => id = *next,
+                other => return other.as_string(),
+            }
+        }
+        None
+    }
+
+    /// Every file path a drawable/mipmap reference can resolve to, ordered
+    /// from the highest density down. Nested references are followed.
+    pub fn resolve_files(&self, id: u32) -> Vec<(u16, String)> {
+        let mut files = Vec::new();
+        let mut pending = vec![id];
+        let mut seen = Vec::new();
+        while let Some(id) = pending.pop() {
+            if seen.contains(&id) || seen.len() > 32 {
+                continue;
+            }
+            seen.push(id);
+            for value in self.values(id) {
+                match &value.value {
+                    AttrValue::Reference(next) => pending.push(*next),
+                    AttrValue::String(path) => files.push((value.density, path.clone())),
+                    _ => {}
+                }
+            }
+        }
+        // "any" (0xfffe) and "none" (0xffff) densities sort above real ones
+        files.sort_by_key(|(density, _)| std::cmp::Reverse(*density));
+        files
+    }
+}
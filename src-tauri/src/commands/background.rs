@@ -0,0 +1,234 @@
+use crate::commands::appops::{fetch_app_ops, run_appops};
+use crate::commands::utils::{adb_shell, resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// App standby buckets (`UsageStatsManager.STANDBY_BUCKET_*`).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StandbyBucket {
+    Exempted,
+    Active,
+    WorkingSet,
+    Frequent,
+    Rare,
+    Restricted,
+    Never,
+}
+
+/// Everything that decides how much an app may do in the background.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BackgroundState {
+    pub standby_bucket: Option<StandbyBucket>,
+    /// On the `deviceidle` whitelist, i.e. exempt from battery optimisation.
+    pub battery_optimization_exempt: Option<bool>,
+    /// True when the whitelist entry comes from the system image and cannot
+    /// be removed.
+    pub system_exempt: bool,
+    /// `RUN_ANY_IN_BACKGROUND` is `ignore` ("Restricted" in battery settings).
+    pub background_restricted: Option<bool>,
+    /// `am get-inactive`.
+    pub inactive: Option<bool>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+impl StandbyBucket {
+    fn from_code(code: u32) -> Option<Self> {
+        match code {
+            5 => Some(Self::Exempted),
+            10 => Some(Self::Active),
+            20 => Some(Self::WorkingSet),
+            30 => Some(Self::Frequent),
+            40 => Some(Self::Rare),
+            45 => Some(Self::Restricted),
+            50 => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// The name `am set-standby-bucket` accepts.
+    fn as_arg(self) -> Option<&'static str> {
+        match self {
+            Self::Active => Some("active"),
+            Self::WorkingSet => Some("working_set"),
+            Self::Frequent => Some("frequent"),
+            Self::Rare => Some("rare"),
+            Self::Restricted => Some("restricted"),
+            Self::Exempted | Self::Never => None,
+        }
+    }
+}
+
+/// `am get-standby-bucket` prints the numeric bucket.
+fn get_standby_bucket(adb_path: &str, device: &str, package: &str) -> Option<StandbyBucket> {
+    let output = adb_shell(
+        adb_path,
+        device,
+        &["am", "get-standby-bucket", &shell_quote(package)],
+    )
+    .ok()?;
+    StandbyBucket::from_code(output.trim().parse().ok()?)
+}
+
+/// `dumpsys deviceidle whitelist` lists `user,<pkg>,<uid>`,
+/// `system,<pkg>,<uid>` and `system-excidle,<pkg>,<uid>` entries.
+fn get_whitelist_state(adb_path: &str, device: &str, package: &str) -> Option<(bool, bool)> {
+    let output = adb_shell(adb_path, device, &["dumpsys", "deviceidle", "whitelist"]).ok()?;
+    let mut exempt = false;
+    let mut system = false;
+    for line in output.lines() {
+        let mut parts = line.trim().split(',');
+        let (Some(kind), Some(pkg)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        if pkg != package {
+            continue;
+        }
+        // system-excidle only exempts from app standby, not from doze
+        if kind == "user" || kind == "system" {
+            exempt = true;
+        }
+        if kind == "system" {
+            system = true;
+        }
+    }
+    Some((exempt, system))
+}
+
+/// `am get-inactive` prints `Idle=true` or `Idle=false`.
+fn get_inactive(adb_path: &str, device: &str, package: &str) -> Option<bool> {
+    let output = adb_shell(
+        adb_path,
+        device,
+        &["am", "get-inactive", &shell_quote(package)],
+    )
+    .ok()?;
+    let value = output.trim().strip_prefix("Idle=")?;
+    Some(value == "true")
+}
+
+pub(crate) fn fetch_background_state(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+) -> BackgroundState {
+    let whitelist = get_whitelist_state(adb_path, device, package);
+    // Ops that were never changed are not listed, which means "allow".
+    let background_restricted = fetch_app_ops(adb_path, device, package, None)
+        .ok()
+        .map(|ops| {
+            ops.iter()
+                .any(|op| op.name == "RUN_ANY_IN_BACKGROUND" && op.mode == "ignore")
+        });
+
+    BackgroundState {
+        standby_bucket: get_standby_bucket(adb_path, device, package),
+        battery_optimization_exempt: whitelist.map(|(exempt, _)| exempt),
+        system_exempt: whitelist.is_some_and(|(_, system)| system),
+        background_restricted,
+        inactive: get_inactive(adb_path, device, package),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_background_state(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<BackgroundState, String> {
+    let adb_path = resolve_adb_path(&app);
+    Ok(fetch_background_state(&adb_path, &device, &package))
+}
+
+#[tauri::command]
+pub async fn set_standby_bucket(
+    app: AppHandle,
+    device: String,
+    package: String,
+    bucket: StandbyBucket,
+) -> Result<BackgroundState, String> {
+    let name = bucket
+        .as_arg()
+        .ok_or_else(|| format!("The {:?} bucket cannot be set manually", bucket))?;
+    let adb_path = resolve_adb_path(&app);
+    adb_shell(
+        &adb_path,
+        &device,
+        &["am", "set-standby-bucket", &shell_quote(&package), name],
+    )?;
+    Ok(fetch_background_state(&adb_path, &device, &package))
+}
+
+/// Adds the package to (or removes it from) the battery optimisation
+/// whitelist with `dumpsys deviceidle whitelist +pkg` / `-pkg`.
+#[tauri::command]
+pub async fn set_battery_optimization_exempt(
+    app: AppHandle,
+    device: String,
+    package: String,
+    exempt: bool,
+) -> Result<BackgroundState, String> {
+    let adb_path = resolve_adb_path(&app);
+    let entry = format!("{}{}", if exempt { "+" } else { "-" }, package);
+    adb_shell(
+        &adb_path,
+        &device,
+        &["dumpsys", "deviceidle", "whitelist", &shell_quote(&entry)],
+    )?;
+
+    let state = fetch_background_state(&adb_path, &device, &package);
+    if !exempt && state.system_exempt {
+        return Err(format!(
+            "{} is whitelisted by the system image and cannot be removed",
+            package
+        ));
+    }
+    Ok(state)
+}
+
+/// Toggles the `RUN_ANY_IN_BACKGROUND` app op, which is what "Restricted"
+/// battery usage in the app settings does.
+#[tauri::command]
+pub async fn set_background_restricted(
+    app: AppHandle,
+    device: String,
+    package: String,
+    restricted: bool,
+) -> Result<BackgroundState, String> {
+    let adb_path = resolve_adb_path(&app);
+    let mode = if restricted { "ignore" } else { "allow" };
+    run_appops(
+        &adb_path,
+        &device,
+        &["set", &shell_quote(&package), "RUN_ANY_IN_BACKGROUND", mode],
+    )?;
+    Ok(fetch_background_state(&adb_path, &device, &package))
+}
+
+#[tauri::command]
+pub async fn set_app_inactive(
+    app: AppHandle,
+    device: String,
+    package: String,
+    inactive: bool,
+) -> Result<BackgroundState, String> {
+    let adb_path = resolve_adb_path(&app);
+    let value = if inactive { "true" } else { "false" };
+    adb_shell(
+        &adb_path,
+        &device,
+        &["am", "set-inactive", &shell_quote(&package), value],
+    )?;
+    Ok(fetch_background_state(&adb_path, &device, &package))
+}
@@ -0,0 +1,330 @@
+use crate::commands::utils::{file_safe_name, resolve_adb_path};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// DropBox tags that hold crash evidence.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CrashTag {
+    #[serde(rename = "data_app_crash")]
+    DataAppCrash,
+    #[serde(rename = "data_app_anr")]
+    DataAppAnr,
+    #[serde(rename = "system_app_crash")]
+    SystemAppCrash,
+    #[serde(rename = "SYSTEM_TOMBSTONE")]
+    SystemTombstone,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CrashEntry {
+    pub tag: CrashTag,
+    /// Device local time as DropBox prints it (`2024-05-01 12:00:00`).
+    pub timestamp: String,
+    pub package_id: Option<String>,
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+    /// Exception class (`java.lang.IllegalStateException`) or, for native
+    /// crashes, the signal name (`SIGSEGV`). `None` for ANRs.
+    pub exception_type: Option<String>,
+    /// Exception message, ANR subject or native abort message.
+    pub message: Option<String>,
+    /// Innermost `Caused by:` exception class, if any.
+    pub root_cause: Option<String>,
+    /// `Key: value` lines DropBox puts before the stack.
+    pub headers: BTreeMap<String, String>,
+    /// Stack trace, ANR traces or tombstone body.
+    pub stack: String,
+    /// The complete entry, as written on export.
+    pub text: String,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const ALL_TAGS: [CrashTag; 4] = [
+    CrashTag::DataAppCrash,
+    CrashTag::DataAppAnr,
+    CrashTag::SystemAppCrash,
+    CrashTag::SystemTombstone,
+];
+
+impl CrashTag {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::DataAppCrash => "data_app_crash",
+            Self::DataAppAnr => "data_app_anr",
+            Self::SystemAppCrash => "system_app_crash",
+            Self::SystemTombstone => "SYSTEM_TOMBSTONE",
+        }
+    }
+
+    fn from_str(tag: &str) -> Option<Self> {
+        ALL_TAGS.into_iter().find(|t| t.as_str() == tag)
+    }
+}
+
+/// `2024-05-01 12:00:00 data_app_crash (text, 1234 bytes)`
+fn parse_entry_header(line: &str) -> Option<(String, CrashTag)> {
+    let mut parts = line.split_whitespace();
+    let date = parts.next()?;
+    let time = parts.next()?;
+    let tag = CrashTag::from_str(parts.next()?)?;
+    if date.len() != 10 || !date.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((format!("{} {}", date, time), tag))
+}
+
+/// Header keys are single words such as `Process`, `Package` or
+/// `Process-Runtime`; this keeps exception lines out of the headers.
+fn parse_header_line(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(": ")?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    is_key.then_some((key, value.trim()))
+}
+
+/// `java.lang.IllegalStateException: message` -> class and message.
+fn parse_exception_line(line: &str) -> Option<(String, Option<String>)> {
+    let line = line.trim();
+    let (class, message) = match line.split_once(": ") {
+        Some((class, message)) => (class, Some(message.trim().to_string())),
+        None => (line, None),
+    };
+    let is_class = class.contains('.') && !class.contains(char::is_whitespace);
+    is_class.then(|| (class.to_string(), message))
+}
+
+/// `pid: 1234, tid: 1250, name: RenderThread  >>> com.example.app <<<`
+fn parse_tombstone_process(line: &str) -> Option<(u32, String)> {
+    let pid = line
+        .strip_prefix("pid: ")?
+        .split(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()?;
+    let name = line.split(">>> ").nth(1)?.split(" <<<").next()?;
+    Some((pid, name.trim().to_string()))
+}
+
+/// `signal 11 (SIGSEGV), code 1 (SEGV_MAPERR), fault addr 0x0` -> `SIGSEGV`
+fn parse_signal_name(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("signal ")?;
+    let name = rest.split_once('(')?.1.split(')').next()?;
+    Some(name.to_string())
+}
+
+fn parse_entry(timestamp: String, tag: CrashTag, lines: &[&str]) -> CrashEntry {
+    let mut headers = BTreeMap::new();
+    let mut body_start = 0;
+    for (index, line) in lines.iter().enumerate() {
+        match parse_header_line(line) {
+            Some((key, value)) => {
+                headers.insert(key.to_string(), value.to_string());
+                body_start = index + 1;
+            }
+            None => break,
+        }
+    }
+    let body: Vec<&str> = lines[body_start..]
+        .iter()
+        .copied()
+        .skip_while(|line| line.trim().is_empty())
+        .collect();
+    let stack = body.join("\n").trim_end().to_string();
+
+    let mut entry = CrashEntry {
+        tag,
+        timestamp,
+        package_id: headers
+            .get("Package")
+            .and_then(|p| p.split_whitespace().next())
+            .map(|p| p.to_string()),
+        process: headers.get("Process").cloned(),
+        pid: headers.get("PID").and_then(|p| p.parse().ok()),
+        exception_type: None,
+        message: None,
+        root_cause: None,
+        headers,
+        stack,
+        text: lines.join("\n").trim_end().to_string(),
+    };
+
+    match tag {
+        CrashTag::DataAppCrash | CrashTag::SystemAppCrash => {
+            if let Some((class, message)) = body.first().and_then(|l| parse_exception_line(l)) {
+                entry.exception_type = Some(class);
+                entry.message = message;
+            }
+            entry.root_cause = body
+                .iter()
+                .rev()
+                .filter_map(|line| line.trim().strip_prefix("Caused by: "))
+                .filter_map(parse_exception_line)
+                .map(|(class, _)| class)
+                .next();
+        }
+        CrashTag::DataAppAnr => {
+            entry.message = entry.headers.get("Subject").cloned();
+        }
+        CrashTag::SystemTombstone => {
+            for line in &body {
+                let line = line.trim();
+                if let Some((pid, process)) = parse_tombstone_process(line) {
+                    entry.pid.get_or_insert(pid);
+                    entry.process.get_or_insert(process);
+                } else if let Some(signal) = parse_signal_name(line) {
+                    entry.exception_type.get_or_insert(signal);
+                } else if let Some(message) = line.strip_prefix("Abort message: ") {
+                    entry
+                        .message
+                        .get_or_insert(message.trim_matches('\'').to_string());
+                }
+            }
+            if entry.package_id.is_none() {
+                // `com.example.app:remote` runs code of `com.example.app`
+                entry.package_id = entry
+                    .process
+                    .as_deref()
+                    .filter(|p| p.contains('.') && !p.starts_with('/'))
+                    .map(|p| p.split(':').next().unwrap_or(p).to_string());
+            }
+        }
+    }
+    entry
+}
+
+/// Splits `dumpsys dropbox --print` output into entries. Each entry starts
+/// after a `====` separator with a `<date> <time> <tag> (...)` line.
+fn parse_dropbox(output: &str) -> Vec<CrashEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<(String, CrashTag)> = None;
+    let mut lines: Vec<&str> = Vec::new();
+
+    for line in output.lines() {
+        // Separator, or the preamble of the next `dumpsys dropbox` call
+        if line.starts_with("========") || line.starts_with("Drop box contents:") {
+            if let Some((timestamp, tag)) = current.take() {
+                entries.push(parse_entry(timestamp, tag, &lines));
+            }
+            lines.clear();
+            continue;
+        }
+        if current.is_none() && lines.is_empty() {
+            if let Some(header) = parse_entry_header(line) {
+                current = Some(header);
+                continue;
+            }
+        }
+        if current.is_some() {
+            lines.push(line);
+        }
+    }
+    if let Some((timestamp, tag)) = current {
+        entries.push(parse_entry(timestamp, tag, &lines));
+    }
+    entries
+}
+
+fn matches_package(entry: &CrashEntry, package: &str) -> bool {
+    entry.package_id.as_deref() == Some(package)
+        || entry.process.as_deref().is_some_and(|process| {
+            process == package
+                || process
+                    .strip_prefix(package)
+                    .is_some_and(|rest| rest.starts_with(':'))
+        })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Lists crash, ANR and native crash entries kept in DropBox, newest first.
+/// `tags` defaults to all four crash tags; `package` keeps entries whose
+/// package or process belongs to it.
+#[tauri::command]
+pub async fn list_crashes(
+    app: AppHandle,
+    device: String,
+    package: Option<String>,
+    tags: Option<Vec<CrashTag>>,
+    limit: Option<usize>,
+) -> Result<Vec<CrashEntry>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let tags = tags.unwrap_or_else(|| ALL_TAGS.to_vec());
+    if tags.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // DropBox only accepts one tag per call
+    let script = tags
+        .iter()
+        .map(|tag| format!("dumpsys dropbox --print {}", tag.as_str()))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let output = Command::new(&adb_path)
+        .args(["-s", &device, "shell", &script])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let mut entries = parse_dropbox(&String::from_utf8_lossy(&output.stdout));
+    if let Some(package) = package.as_deref().filter(|p| !p.is_empty()) {
+        entries.retain(|entry| matches_package(entry, package));
+    }
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    if let Some(limit) = limit {
+        entries.truncate(limit);
+    }
+    Ok(entries)
+}
+
+/// Writes each entry to `dest` as `<timestamp>_<tag>_<package>.txt` and
+/// returns the written paths.
+#[tauri::command]
+pub async fn export_crashes(entries: Vec<CrashEntry>, dest: String) -> Result<Vec<String>, String> {
+    let dest = Path::new(&dest);
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create folder: {}", e))?;
+
+    let mut used = HashSet::new();
+    let mut written = Vec::with_capacity(entries.len());
+    for entry in &entries {
+        let stem = file_safe_name(&format!(
+            "{}_{}_{}",
+            entry.timestamp,
+            entry.tag.as_str(),
+            entry
+                .package_id
+                .as_deref()
+                .or(entry.process.as_deref())
+                .unwrap_or("unknown")
+        ));
+        let mut name = format!("{}.txt", stem);
+        let mut n = 2;
+        while !used.insert(name.clone()) || dest.join(&name).exists() {
+            name = format!("{}-{}.txt", stem, n);
+            n += 1;
+        }
+
+        let path = dest.join(&name);
+        fs::write(&path, format!("{}\n", entry.text))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        written.push(path.to_string_lossy().to_string());
+    }
+    Ok(written)
+}
@@ -0,0 +1,444 @@
+use crate::commands::adb::get_prop;
+use crate::commands::packages::{run_package_action, BatchItemResult, PackageAction};
+use crate::commands::utils::{
+    adb_shell, app_data_subdir, file_safe_name, resolve_adb_path, shell_quote,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DebloatMethod {
+    /// `pm uninstall -k --user N`: the APK stays on the system partition and
+    /// the app can be brought back with `install-existing`.
+    UninstallForUser,
+    /// `pm disable-user --user N`.
+    Disable,
+}
+
+/// Risk levels as used by the common community debloat lists, from safest to
+/// most likely to break the device. `Unknown` sorts last, so unrated entries
+/// are only applied when asked for explicitly.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    #[serde(alias = "Recommended", alias = "safe", alias = "Safe")]
+    Recommended,
+    #[serde(alias = "Advanced")]
+    Advanced,
+    #[serde(alias = "Expert")]
+    Expert,
+    #[serde(alias = "Unsafe")]
+    Unsafe,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JournalEntry {
+    pub package_id: String,
+    pub method: DebloatMethod,
+    pub user_id: u32,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Everything removed or disabled on one device, keyed by `ro.serialno` so
+/// the journal survives switching between USB and wireless adb.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DebloatJournal {
+    pub device_id: String,
+    pub entries: Vec<JournalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DebloatListEntry {
+    #[serde(alias = "package_id", alias = "package")]
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, alias = "removal")]
+    pub risk: RiskLevel,
+}
+
+/// An imported debloat list, stored under a name so it can be applied to
+/// any device later.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DebloatProfile {
+    pub name: String,
+    pub entries: Vec<DebloatListEntry>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Serialises journal read-modify-write cycles between concurrent commands.
+static JOURNAL_LOCK: Mutex<()> = Mutex::new(());
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn device_id(adb_path: &str, device: &str) -> String {
+    get_prop(adb_path, device, "ro.serialno").unwrap_or_else(|| device.to_string())
+}
+
+fn journal_path(app: &AppHandle, device_id: &str) -> Result<PathBuf, String> {
+    Ok(app_data_subdir(app, "debloat")?.join(format!("{}.json", file_safe_name(device_id))))
+}
+
+fn profile_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app_data_subdir(app, "debloat/profiles")
+}
+
+fn load_journal(path: &Path, device_id: &str) -> Result<DebloatJournal, String> {
+    if !path.exists() {
+        return Ok(DebloatJournal {
+            device_id: device_id.to_string(),
+            entries: Vec::new(),
+        });
+    }
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read journal: {}", e))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Corrupt journal: {}", e))
+}
+
+fn save_journal(path: &Path, journal: &DebloatJournal) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(journal).map_err(|e| e.to_string())?;
+    fs::write(path, bytes).map_err(|e| format!("Failed to write journal: {}", e))
+}
+
+/// Loads the device's journal, applies `update` and writes it back.
+fn update_journal<T>(
+    app: &AppHandle,
+    device_id: &str,
+    update: impl FnOnce(&mut DebloatJournal) -> T,
+) -> Result<T, String> {
+    let _guard = JOURNAL_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = journal_path(app, device_id)?;
+    let mut journal = load_journal(&path, device_id)?;
+    let result = update(&mut journal);
+    save_journal(&path, &journal)?;
+    Ok(result)
+}
+
+/// Appends `added`, replacing older entries for the same package, user and
+/// method. A package both uninstalled and disabled keeps one entry for each,
+/// since restoring only the newer one would leave it uninstalled.
+fn record_entries(journal: &mut DebloatJournal, added: Vec<JournalEntry>) {
+    journal.entries.retain(|e| {
+        !added
+            .iter()
+            .any(|a| a.package_id == e.package_id && a.user_id == e.user_id && a.method == e.method)
+    });
+    journal.entries.extend(added);
+}
+
+fn remove_package(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    method: DebloatMethod,
+    user_id: u32,
+) -> Result<(), String> {
+    match method {
+        DebloatMethod::UninstallForUser => {
+            let user = user_id.to_string();
+            let stdout = adb_shell(
+                adb_path,
+                device,
+                &[
+                    "pm",
+                    "uninstall",
+                    "-k",
+                    "--user",
+                    &user,
+                    &shell_quote(package),
+                ],
+            )?;
+            if !stdout.contains("Success") {
+                return Err(stdout.trim().to_string());
+            }
+            Ok(())
+        }
+        DebloatMethod::Disable => run_package_action(
+            adb_path,
+            device,
+            package,
+            PackageAction::Disable,
+            Some(user_id),
+        ),
+    }
+}
+
+fn restore_entry(adb_path: &str, device: &str, entry: &JournalEntry) -> Result<(), String> {
+    match entry.method {
+        DebloatMethod::UninstallForUser => {
+            let user = entry.user_id.to_string();
+            let stdout = adb_shell(
+                adb_path,
+                device,
+                &[
+                    "cmd",
+                    "package",
+                    "install-existing",
+                    "--user",
+                    &user,
+                    &shell_quote(&entry.package_id),
+                ],
+            )?;
+            if !stdout.contains("installed for user") {
+                return Err(stdout.trim().to_string());
+            }
+            Ok(())
+        }
+        DebloatMethod::Disable => run_package_action(
+            adb_path,
+            device,
+            &entry.package_id,
+            PackageAction::Enable,
+            Some(entry.user_id),
+        ),
+    }
+}
+
+/// Accepts a JSON array of `{ id, description, risk }` objects (the field
+/// names of the common community lists are accepted too), or a plain text
+/// file with one package ID per line and `#` comments.
+fn parse_debloat_list(content: &str) -> Result<Vec<DebloatListEntry>, String> {
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(content).map_err(|e| format!("Invalid debloat list: {}", e));
+    }
+
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|id| DebloatListEntry {
+            id: id.to_string(),
+            description: String::new(),
+            risk: RiskLevel::Unknown,
+        })
+        .collect())
+}
+
+fn result_of(package_id: &str, outcome: Result<(), String>) -> BatchItemResult {
+    BatchItemResult {
+        package_id: package_id.to_string(),
+        success: outcome.is_ok(),
+        error: outcome.err(),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Removes or disables a (system) package for one user and records it in the
+/// device's journal so it can be restored later.
+#[tauri::command]
+pub async fn debloat_package(
+    app: AppHandle,
+    device: String,
+    package: String,
+    method: DebloatMethod,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let adb_path = resolve_adb_path(&app);
+    let user_id = user_id.unwrap_or(0);
+    remove_package(&adb_path, &device, &package, method, user_id)?;
+
+    let device_id = device_id(&adb_path, &device);
+    update_journal(&app, &device_id, |journal| {
+        record_entries(
+            journal,
+            vec![JournalEntry {
+                package_id: package.clone(),
+                method,
+                user_id,
+                timestamp: now_millis(),
+            }],
+        );
+    })
+}
+
+#[tauri::command]
+pub async fn get_debloat_journal(app: AppHandle, device: String) -> Result<DebloatJournal, String> {
+    let adb_path = resolve_adb_path(&app);
+    let device_id = device_id(&adb_path, &device);
+    let _guard = JOURNAL_LOCK.lock().map_err(|e| e.to_string())?;
+    load_journal(&journal_path(&app, &device_id)?, &device_id)
+}
+
+/// Restores one journaled package. With no `user_id`, every user it was
+/// removed from is restored.
+#[tauri::command]
+pub async fn restore_debloated_package(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let adb_path = resolve_adb_path(&app);
+    let device_id = device_id(&adb_path, &device);
+    let _guard = JOURNAL_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = journal_path(&app, &device_id)?;
+    let mut journal = load_journal(&path, &device_id)?;
+
+    let matches =
+        |e: &JournalEntry| e.package_id == package && user_id.is_none_or(|user| e.user_id == user);
+    if !journal.entries.iter().any(matches) {
+        return Err(format!("{} is not in the debloat journal", package));
+    }
+
+    // Newest first, so a package that was uninstalled and then disabled is
+    // installed again before it is enabled
+    let mut first_error = None;
+    let mut remaining = Vec::new();
+    for entry in journal.entries.drain(..).rev() {
+        if !matches(&entry) {
+            remaining.push(entry);
+            continue;
+        }
+        if let Err(e) = restore_entry(&adb_path, &device, &entry) {
+            first_error.get_or_insert(e);
+            remaining.push(entry);
+        }
+    }
+    remaining.reverse();
+    journal.entries = remaining;
+    save_journal(&path, &journal)?;
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+/// Reverts everything in the device's journal, newest first. Entries that
+/// fail to restore stay in the journal.
+#[tauri::command]
+pub async fn restore_all_debloated(
+    app: AppHandle,
+    device: String,
+) -> Result<Vec<BatchItemResult>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let device_id = device_id(&adb_path, &device);
+    let _guard = JOURNAL_LOCK.lock().map_err(|e| e.to_string())?;
+    let path = journal_path(&app, &device_id)?;
+    let mut journal = load_journal(&path, &device_id)?;
+
+    let mut results = Vec::new();
+    let mut remaining = Vec::new();
+    for entry in journal.entries.drain(..).rev() {
+        let outcome = restore_entry(&adb_path, &device, &entry);
+        if outcome.is_err() {
+            remaining.push(entry.clone());
+        }
+        results.push(result_of(&entry.package_id, outcome));
+    }
+    remaining.reverse();
+    journal.entries = remaining;
+    save_journal(&path, &journal)?;
+
+    Ok(results)
+}
+
+/// Imports a debloat list from `path` and stores it as profile `name`.
+#[tauri::command]
+pub async fn import_debloat_list(
+    app: AppHandle,
+    path: String,
+    name: String,
+) -> Result<DebloatProfile, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read list: {}", e))?;
+    let profile = DebloatProfile {
+        name: name.clone(),
+        entries: parse_debloat_list(&content)?,
+    };
+
+    let file = profile_dir(&app)?.join(format!("{}.json", file_safe_name(&name)));
+    let bytes = serde_json::to_vec_pretty(&profile).map_err(|e| e.to_string())?;
+    fs::write(file, bytes).map_err(|e| format!("Failed to save profile: {}", e))?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn list_debloat_profiles(app: AppHandle) -> Result<Vec<DebloatProfile>, String> {
+    let dir = profile_dir(&app)?;
+    let mut profiles: Vec<DebloatProfile> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read(entry.path()).ok())
+        .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+        .collect();
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// Applies every entry of profile `name` up to `max_risk` that is installed
+/// for the user. Packages that are not installed are skipped silently.
+#[tauri::command]
+pub async fn apply_debloat_profile(
+    app: AppHandle,
+    device: String,
+    name: String,
+    max_risk: RiskLevel,
+    method: DebloatMethod,
+    user_id: Option<u32>,
+) -> Result<Vec<BatchItemResult>, String> {
+    let file = profile_dir(&app)?.join(format!("{}.json", file_safe_name(&name)));
+    let bytes = fs::read(&file).map_err(|_| format!("Profile {} not found", name))?;
+    let profile: DebloatProfile =
+        serde_json::from_slice(&bytes).map_err(|e| format!("Corrupt profile: {}", e))?;
+
+    let adb_path = resolve_adb_path(&app);
+    let user_id = user_id.unwrap_or(0);
+    let user = user_id.to_string();
+    let installed: HashSet<String> = adb_shell(
+        &adb_path,
+        &device,
+        &["pm", "list", "packages", "--user", &user],
+    )?
+    .lines()
+    .filter_map(|line| line.trim().strip_prefix("package:"))
+    .map(|pkg| pkg.to_string())
+    .collect();
+
+    let mut results = Vec::new();
+    let mut applied = Vec::new();
+    for entry in &profile.entries {
+        if entry.risk > max_risk || !installed.contains(&entry.id) {
+            continue;
+        }
+        let outcome = remove_package(&adb_path, &device, &entry.id, method, user_id);
+        if outcome.is_ok() {
+            applied.push(JournalEntry {
+                package_id: entry.id.clone(),
+                method,
+                user_id,
+                timestamp: now_millis(),
+            });
+        }
+        results.push(result_of(&entry.id, outcome));
+    }
+
+    let device_id = device_id(&adb_path, &device);
+    update_journal(&app, &device_id, |journal| record_entries(journal, applied))?;
+
+    Ok(results)
+}
@@ -0,0 +1,453 @@
+use crate::commands::intents::{run_intent, IntentSpec, IntentTarget, SendIntentOptions};
+use crate::commands::utils::{adb_shell, resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PathMatcher {
+    /// `LITERAL`, `PREFIX`, `GLOB`, `ADVANCED_GLOB` or `SUFFIX`.
+    pub kind: String,
+    pub pattern: String,
+}
+
+/// One `VIEW` intent filter with data, taken from the activity resolver
+/// table of `dumpsys package`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeepLinkFilter {
+    pub activity: String,
+    pub schemes: Vec<String>,
+    pub hosts: Vec<String>,
+    pub paths: Vec<PathMatcher>,
+    pub browsable: bool,
+    /// `android:autoVerify`, i.e. an App Link candidate.
+    pub auto_verify: bool,
+    /// Example URLs built from the filter, for quick testing.
+    pub example_urls: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeepLinkTestResult {
+    pub url: String,
+    /// The component `resolve-activity` picked, before launching.
+    pub resolved_activity: Option<String>,
+    /// True when the URL resolved to the system chooser instead of one app.
+    pub ambiguous: bool,
+    /// Whether the URL resolved into the package under test.
+    pub handled_by_package: Option<bool>,
+    pub launched: bool,
+    /// The activity `am start -W` reported, when launched.
+    pub launched_activity: Option<String>,
+    pub output: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DomainVerification {
+    pub domain: String,
+    /// `verified`, `approved`, `denied`, `none`, `legacy_failure`, ... or a
+    /// numeric error code while verification has failed.
+    pub state: String,
+    /// Whether the user explicitly enabled (true) or disabled (false) the
+    /// domain in the app's "Open by default" settings.
+    pub user_selected: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AppLinksStatus {
+    pub package_id: String,
+    pub verification_id: Option<String>,
+    pub link_handling_allowed: Option<bool>,
+    pub domains: Vec<DomainVerification>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const RESOLVER_ACTIVITIES: &[&str] = &["ResolverActivity", "ChooserActivity", "IntentForwarder"];
+
+#[derive(Default)]
+struct RawFilter {
+    id: String,
+    activity: String,
+    actions: Vec<String>,
+    categories: Vec<String>,
+    schemes: Vec<String>,
+    hosts: Vec<String>,
+    paths: Vec<PathMatcher>,
+    auto_verify: bool,
+}
+
+fn quoted_value(line: &str) -> Option<String> {
+    let start = line.find('"')? + 1;
+    let end = line[start..].find('"')? + start;
+    Some(line[start..end].to_string())
+}
+
+/// `Path: "PatternMatcher{PREFIX: /products}"`
+fn parse_path_matcher(line: &str) -> Option<PathMatcher> {
+    let inner = line.split("PatternMatcher{").nth(1)?.strip_suffix("}\"")?;
+    let (kind, pattern) = inner.split_once(": ")?;
+    Some(PathMatcher {
+        kind: kind.to_string(),
+        pattern: pattern.to_string(),
+    })
+}
+
+fn example_urls(filter: &RawFilter) -> Vec<String> {
+    let hosts: Vec<&str> = if filter.hosts.is_empty() {
+        vec![""]
+    } else {
+        filter.hosts.iter().map(|h| h.as_str()).collect()
+    };
+    let paths: Vec<String> = if filter.paths.is_empty() {
+        vec![String::new()]
+    } else {
+        filter
+            .paths
+            .iter()
+            .map(|p| match p.kind.as_str() {
+                "LITERAL" | "PREFIX" => p.pattern.clone(),
+                // Turn simple globs into something that matches them.
+                _ => p.pattern.replace(".*", "example").replace('*', ""),
+            })
+            .collect()
+    };
+
+    let mut urls = Vec::new();
+    for scheme in &filter.schemes {
+        for host in &hosts {
+            for path in &paths {
+                let host = host.trim_start_matches("*.");
+                let url = if host.is_empty() {
+                    format!("{}:{}", scheme, path)
+                } else {
+                    format!("{}://{}{}", scheme, host, path)
+                };
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+        }
+    }
+    urls
+}
+
+/// Parses the `Activity Resolver Table:` of `dumpsys package <pkg>`. A filter
+/// is listed once per scheme it declares, so filters are de-duplicated by
+/// their id.
+fn parse_deep_links(dump: &str) -> Vec<DeepLinkFilter> {
+    let mut filters: Vec<RawFilter> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut in_table = false;
+    let mut current: Option<RawFilter> = None;
+
+    let mut finish = |current: &mut Option<RawFilter>, filters: &mut Vec<RawFilter>| {
+        if let Some(filter) = current.take() {
+            if seen.insert(filter.id.clone()) {
+                filters.push(filter);
+            }
+        }
+    };
+
+    for line in dump.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            finish(&mut current, &mut filters);
+            in_table = line.starts_with("Activity Resolver Table:");
+            continue;
+        }
+        if !in_table {
+            continue;
+        }
+
+        let trimmed = line.trim();
+        // 5b8c1f2 com.example.app/.LinkActivity filter 9e4d7a1
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        if parts.len() == 4 && parts[2] == "filter" && parts[1].contains('/') {
+            finish(&mut current, &mut filters);
+            current = Some(RawFilter {
+                id: parts[3].to_string(),
+                activity: parts[1].to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(filter) = current.as_mut() else {
+            continue;
+        };
+        if trimmed.starts_with("Action:") {
+            filter.actions.extend(quoted_value(trimmed));
+        } else if trimmed.starts_with("Category:") {
+            filter.categories.extend(quoted_value(trimmed));
+        } else if trimmed.starts_with("Scheme:") {
+            filter.schemes.extend(quoted_value(trimmed));
+        } else if trimmed.starts_with("Authority:") {
+            filter.hosts.extend(quoted_value(trimmed));
+        } else if trimmed.starts_with("Path:") {
+            filter.paths.extend(parse_path_matcher(trimmed));
+        } else if trimmed.starts_with("AutoVerify=true") || trimmed == "autoVerify=true" {
+            filter.auto_verify = true;
+        }
+    }
+    finish(&mut current, &mut filters);
+
+    filters
+        .into_iter()
+        .filter(|f| f.actions.iter().any(|a| a == "android.intent.action.VIEW"))
+        .filter(|f| !f.schemes.is_empty())
+        .map(|f| DeepLinkFilter {
+            example_urls: example_urls(&f),
+            browsable: f
+                .categories
+                .iter()
+                .any(|c| c == "android.intent.category.BROWSABLE"),
+            activity: f.activity,
+            schemes: f.schemes,
+            hosts: f.hosts,
+            paths: f.paths,
+            auto_verify: f.auto_verify,
+        })
+        .collect()
+}
+
+/// Parses `pm get-app-links` (Android 12+):
+///
+/// ```text
+///   com.example.app:
+///     ID: 1a2b3c4d-...
+///     Signatures: [...]
+///     Domain verification state:
+///       example.com: verified
+///       www.example.com: 1024
+///     User 0:
+///       Verification link handling allowed: true
+///       Selection state:
+///         Disabled:
+///           www.example.com
+/// ```
+fn parse_app_links(package: &str, output: &str) -> AppLinksStatus {
+    let mut status = AppLinksStatus {
+        package_id: package.to_string(),
+        ..Default::default()
+    };
+
+    #[derive(PartialEq)]
+    enum Section {
+        None,
+        States,
+        Enabled,
+        Disabled,
+    }
+    let mut section = Section::None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(id) = trimmed.strip_prefix("ID: ") {
+            status.verification_id = Some(id.to_string());
+        } else if trimmed == "Domain verification state:" {
+            section = Section::States;
+        } else if let Some(allowed) = trimmed.strip_prefix("Verification link handling allowed: ") {
+            status.link_handling_allowed = Some(allowed == "true");
+        } else if trimmed == "Enabled:" {
+            section = Section::Enabled;
+        } else if trimmed == "Disabled:" {
+            section = Section::Disabled;
+        } else if trimmed.ends_with(':') || trimmed.contains(": ") {
+            match (&section, trimmed.split_once(": ")) {
+                (Section::States, Some((domain, state))) => {
+                    status.domains.push(DomainVerification {
+                        domain: domain.to_string(),
+                        state: state.to_string(),
+                        user_selected: None,
+                    })
+                }
+                _ => section = Section::None,
+            }
+        } else if !trimmed.is_empty() && section != Section::None {
+            let selected = section == Section::Enabled;
+            if let Some(domain) = status.domains.iter_mut().find(|d| d.domain == trimmed) {
+                domain.user_selected = Some(selected);
+            }
+        }
+    }
+
+    status
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_deep_links(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<Vec<DeepLinkFilter>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let dump = adb_shell(
+        &adb_path,
+        &device,
+        &["dumpsys", "package", &shell_quote(&package)],
+    )?;
+    Ok(parse_deep_links(&dump))
+}
+
+/// Resolves `url` as a browser would (`VIEW` + `BROWSABLE`) and, if `launch`
+/// is set, fires it with `am start -W`.
+#[tauri::command]
+pub async fn test_deep_link(
+    app: AppHandle,
+    device: String,
+    url: String,
+    package: Option<String>,
+    launch: bool,
+) -> Result<DeepLinkTestResult, String> {
+    let adb_path = resolve_adb_path(&app);
+    let quoted_url = shell_quote(&url);
+
+    let resolved = adb_shell(
+        &adb_path,
+        &device,
+        &[
+            "cmd",
+            "package",
+            "resolve-activity",
+            "--brief",
+            "-a",
+            "android.intent.action.VIEW",
+            "-c",
+            "android.intent.category.BROWSABLE",
+            "-d",
+            &quoted_url,
+        ],
+    )?;
+    // The last line is the component, or "No activity found".
+    let resolved_activity = resolved
+        .lines()
+        .map(|line| line.trim())
+        .rfind(|line| line.contains('/'))
+        .map(|line| line.to_string());
+    let ambiguous = resolved_activity
+        .as_deref()
+        .is_some_and(|a| RESOLVER_ACTIVITIES.iter().any(|r| a.contains(r)));
+    let handled_by_package = package.as_ref().map(|package| {
+        resolved_activity
+            .as_deref()
+            .is_some_and(|a| a.starts_with(&format!("{}/", package)))
+    });
+
+    let mut result = DeepLinkTestResult {
+        url: url.clone(),
+        resolved_activity,
+        ambiguous,
+        handled_by_package,
+        launched: false,
+        launched_activity: None,
+        output: resolved.trim().to_string(),
+    };
+    if !launch {
+        return Ok(result);
+    }
+
+    let intent = IntentSpec {
+        action: Some("android.intent.action.VIEW".to_string()),
+        data: Some(url),
+        categories: vec!["android.intent.category.BROWSABLE".to_string()],
+        ..Default::default()
+    };
+    let options = SendIntentOptions {
+        wait: true,
+        ..Default::default()
+    };
+    let launched = run_intent(
+        &adb_path,
+        &device,
+        IntentTarget::Activity,
+        &intent,
+        &options,
+    )?;
+    result.launched = launched.success;
+    result.launched_activity = launched.timing.and_then(|t| t.activity);
+    result.output = launched.output;
+    Ok(result)
+}
+
+/// Domain verification state from `pm get-app-links` (Android 12+).
+#[tauri::command]
+pub async fn get_app_links(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<AppLinksStatus, String> {
+    let adb_path = resolve_adb_path(&app);
+    let output = adb_shell(
+        &adb_path,
+        &device,
+        &["pm", "get-app-links", &shell_quote(&package)],
+    )?;
+    if output.trim().is_empty() {
+        return Err(format!("No App Links information for {}", package));
+    }
+    Ok(parse_app_links(&package, &output))
+}
+
+/// `pm set-app-links --package <pkg> <state> <domains|all>`. State `0`
+/// (`STATE_NO_RESPONSE`) resets the domains so they can be verified again.
+#[tauri::command]
+pub async fn set_app_links(
+    app: AppHandle,
+    device: String,
+    package: String,
+    state: String,
+    domains: Vec<String>,
+) -> Result<(), String> {
+    let adb_path = resolve_adb_path(&app);
+    let mut args = vec![
+        "pm".to_string(),
+        "set-app-links".to_string(),
+        "--package".to_string(),
+        shell_quote(&package),
+        shell_quote(&state),
+    ];
+    if domains.is_empty() {
+        args.push("all".to_string());
+    } else {
+        args.extend(domains.iter().map(|d| shell_quote(d)));
+    }
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    adb_shell(&adb_path, &device, &args).map(|_| ())
+}
+
+/// Asks the verifier to check the package's domains again, then returns the
+/// resulting state. Verification runs asynchronously on the device, so
+/// domains may still show as pending right after this returns.
+#[tauri::command]
+pub async fn verify_app_links(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<AppLinksStatus, String> {
+    let adb_path = resolve_adb_path(&app);
+    adb_shell(
+        &adb_path,
+        &device,
+        &[
+            "pm",
+            "verify-app-links",
+            "--re-verify",
+            &shell_quote(&package),
+        ],
+    )?;
+    let output = adb_shell(
+        &adb_path,
+        &device,
+        &["pm", "get-app-links", &shell_quote(&package)],
+    )?;
+    Ok(parse_app_links(&package, &output))
+}
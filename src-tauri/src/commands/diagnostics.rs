@@ -79,6 +79,15 @@ pub struct TouchTestResult {
     pub raw_events: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DozeState {
+    pub deep: Option<String>, // "ACTIVE", "IDLE_PENDING", "SENSING", "IDLE", ...
+    pub light: Option<String>, // "ACTIVE", "IDLE", "WAITING_FOR_NETWORK", ...
+    pub forced: Option<bool>,
+    pub screen_on: Option<bool>,
+    pub charging: Option<bool>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FullDiagnostics {
     pub battery: BatteryDiagnostics,
@@ -606,6 +615,34 @@ fn run_touch_test_internal(adb_path: &str, serial: &str) -> TouchTestResult {
     }
 }
 
+// ============================================================================
+// Doze
+// ============================================================================
+
+fn get_deviceidle_value(adb_path: &str, serial: &str, key: &str) -> Option<String> {
+    run_shell_command(adb_path, serial, &format!("dumpsys deviceidle get {}", key))
+}
+
+fn get_doze_state_internal(adb_path: &str, serial: &str) -> DozeState {
+    let as_bool = |v: String| v == "true";
+    DozeState {
+        deep: get_deviceidle_value(adb_path, serial, "deep"),
+        light: get_deviceidle_value(adb_path, serial, "light"),
+        forced: get_deviceidle_value(adb_path, serial, "force").map(as_bool),
+        screen_on: get_deviceidle_value(adb_path, serial, "screen").map(as_bool),
+        charging: get_deviceidle_value(adb_path, serial, "charging").map(as_bool),
+    }
+}
+
+/// Only "deep" and "light" are valid idle modes
+fn doze_mode(mode: Option<String>) -> Result<String, String> {
+    match mode.as_deref() {
+        None | Some("deep") => Ok("deep".to_string()),
+        Some("light") => Ok("light".to_string()),
+        Some(other) => Err(format!("Unknown idle mode: {}", other)),
+    }
+}
+
 // ============================================================================
 // Commands
 // ============================================================================
@@ -727,6 +764,61 @@ pub async fn reset_battery_simulation(app: AppHandle, serial: String) -> Result<
     run_shell_action(&adb_path, &serial, "dumpsys battery reset")
 }
 
+#[tauri::command]
+pub async fn get_doze_state(app: AppHandle, serial: String) -> Result<DozeState, String> {
+    let adb_path = resolve_adb_path(&app);
+    Ok(get_doze_state_internal(&adb_path, &serial))
+}
+
+#[tauri::command]
+pub async fn force_doze(
+    app: AppHandle,
+    serial: String,
+    mode: Option<String>,
+) -> Result<DozeState, String> {
+    let adb_path = resolve_adb_path(&app);
+    let mode = doze_mode(mode)?;
+    // Doze never kicks in while charging
+    run_shell_action(&adb_path, &serial, "dumpsys battery unplug")?;
+    let output = run_shell_command(
+        &adb_path,
+        &serial,
+        &format!("dumpsys deviceidle force-idle {}", mode),
+    )
+    .unwrap_or_default();
+    // e.g. "Unable to go deep idle; not enabled"
+    if output.starts_with("Unable") {
+        return Err(output);
+    }
+    Ok(get_doze_state_internal(&adb_path, &serial))
+}
+
+/// Moves one step through the idle state machine, e.g. ACTIVE -> IDLE_PENDING
+#[tauri::command]
+pub async fn step_doze(
+    app: AppHandle,
+    serial: String,
+    mode: Option<String>,
+) -> Result<DozeState, String> {
+    let adb_path = resolve_adb_path(&app);
+    let mode = doze_mode(mode)?;
+    run_shell_action(&adb_path, &serial, "dumpsys battery unplug")?;
+    run_shell_action(
+        &adb_path,
+        &serial,
+        &format!("dumpsys deviceidle step {}", mode),
+    )?;
+    Ok(get_doze_state_internal(&adb_path, &serial))
+}
+
+#[tauri::command]
+pub async fn unforce_doze(app: AppHandle, serial: String) -> Result<DozeState, String> {
+    let adb_path = resolve_adb_path(&app);
+    run_shell_action(&adb_path, &serial, "dumpsys deviceidle unforce")?;
+    run_shell_action(&adb_path, &serial, "dumpsys battery reset")?;
+    Ok(get_doze_state_internal(&adb_path, &serial))
+}
+
 #[tauri::command]
 pub async fn trigger_vibration(
     app: AppHandle,
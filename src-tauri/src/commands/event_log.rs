@@ -0,0 +1,226 @@
+//! Decoding of the binary `events` log buffer, as read with
+//! `logcat -b events -B`.
+//!
+//! Each record is a `logger_entry` header followed by a payload holding a
+//! 32-bit tag number and one encoded value. Tag and field names come from
+//! the device's `event-log-tags` file:
+//!
+//! ```text
+//! 30011 am_proc_start (User|1|5),(PID|1|5),(UID|1|5),(Process Name|3),(Type|3),(Component|3)
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const TYPE_INT: u8 = 0;
+const TYPE_LONG: u8 = 1;
+const TYPE_STRING: u8 = 2;
+const TYPE_LIST: u8 = 3;
+const TYPE_FLOAT: u8 = 4;
+
+/// Header size of the original `logger_entry`, which has no size field.
+const V1_HEADER_SIZE: usize = 20;
+/// `logger_entry_v4` and later carry the UID at offset 24.
+const V4_HEADER_SIZE: usize = 28;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", content = "value", rename_all = "lowercase")]
+pub enum EventValue {
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    String(String),
+    List(Vec<EventValue>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventField {
+    /// Field name from `event-log-tags`; `None` for undescribed values.
+    pub name: Option<String>,
+    pub value: EventValue,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EventLogEntry {
+    /// Milliseconds since the Unix epoch, device clock.
+    pub timestamp_ms: u64,
+    pub pid: u32,
+    pub tid: u32,
+    /// Only recorded by Android 7 and later.
+    pub uid: Option<u32>,
+    pub tag_id: u32,
+    /// `None` for tags missing from `event-log-tags`.
+    pub tag: Option<String>,
+    pub fields: Vec<EventField>,
+}
+
+pub struct EventTag {
+    pub name: String,
+    pub fields: Vec<String>,
+}
+
+/// Parses `event-log-tags`: `<number> <name> [(<field>|<type>[|<unit>]),...]`
+/// per line, `#` starting a comment.
+pub fn parse_event_log_tags(text: &str) -> HashMap<u32, EventTag> {
+    let mut tags = HashMap::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(3, char::is_whitespace);
+        let (Some(number), Some(name)) = (parts.next(), parts.next()) else {
+            continue;
+        };
+        let Ok(number) = number.parse() else {
+            continue;
+        };
+        let fields = parts
+            .next()
+            .unwrap_or("")
+            .split(')')
+            .filter_map(|field| {
+                let field = field
+                    .trim_start_matches([',', ' ', '\t'])
+                    .strip_prefix('(')?;
+                Some(field.split('|').next()?.trim().to_string())
+            })
+            .collect();
+        tags.insert(
+            number,
+            EventTag {
+                name: name.to_string(),
+                fields,
+            },
+        );
+    }
+    tags
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(pos..pos + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(pos..pos + 8)?.try_into().ok()?))
+}
+
+/// Decodes the value at `*pos`: a type byte followed by its data.
+fn decode_value(payload: &[u8], pos: &mut usize) -> Option<EventValue> {
+    let kind = *payload.get(*pos)?;
+    *pos += 1;
+    let value = match kind {
+        TYPE_INT => {
+            let value = read_u32(payload, *pos)? as i32;
+            *pos += 4;
+            EventValue::Int(value)
+        }
+        TYPE_LONG => {
+            let value = read_u64(payload, *pos)? as i64;
+            *pos += 8;
+            EventValue::Long(value)
+        }
+        TYPE_FLOAT => {
+            let value = f32::from_bits(read_u32(payload, *pos)?);
+            *pos += 4;
+            EventValue::Float(value)
+        }
+        TYPE_STRING => {
+            let len = read_u32(payload, *pos)? as usize;
+            *pos += 4;
+            let bytes = payload.get(*pos..pos.checked_add(len)?)?;
+            *pos += len;
+            EventValue::String(String::from_utf8_lossy(bytes).to_string())
+        }
+        TYPE_LIST => {
+            let count = *payload.get(*pos)?;
+            *pos += 1;
+            let mut items = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                items.push(decode_value(payload, pos)?);
+            }
+            EventValue::List(items)
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Pairs the decoded value with the field names of its tag. A list is
+/// split into its fields unless the tag declares a single (list) field.
+fn name_fields(value: EventValue, names: &[String]) -> Vec<EventField> {
+    match value {
+        EventValue::List(items) if names.len() != 1 => items
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| EventField {
+                name: names.get(index).cloned(),
+                value,
+            })
+            .collect(),
+        value => vec![EventField {
+            name: names.first().cloned(),
+            value,
+        }],
+    }
+}
+
+/// Decodes the records of `logcat -B` output. Stops at the first record
+/// that is cut off.
+pub fn decode_event_log(data: &[u8], tags: &HashMap<u32, EventTag>) -> Vec<EventLogEntry> {
+    let mut entries = Vec::new();
+    let mut pos = 0;
+    while let (Some(len), Some(header_size)) = (read_u16(data, pos), read_u16(data, pos + 2)) {
+        let header_size = match header_size as usize {
+            0 => V1_HEADER_SIZE,
+            size if size < V1_HEADER_SIZE => break,
+            size => size,
+        };
+        let start = pos + header_size;
+        let end = start + len as usize;
+        if end > data.len() {
+            break;
+        }
+        let record = &data[pos..end];
+        let payload = &data[start..end];
+        pos = end;
+
+        let (Some(pid), Some(tid), Some(sec), Some(nsec), Some(tag_id)) = (
+            read_u32(record, 4),
+            read_u32(record, 8),
+            read_u32(record, 12),
+            read_u32(record, 16),
+            read_u32(payload, 0),
+        ) else {
+            continue;
+        };
+        let uid = if header_size >= V4_HEADER_SIZE {
+            read_u32(record, 24)
+        } else {
+            None
+        };
+
+        let tag = tags.get(&tag_id);
+        let names = tag.map(|tag| tag.fields.as_slice()).unwrap_or_default();
+        // Values logd truncated to its maximum payload are left out
+        let mut value_pos = 4;
+        let fields = decode_value(payload, &mut value_pos)
+            .map(|value| name_fields(value, names))
+            .unwrap_or_default();
+
+        entries.push(EventLogEntry {
+            timestamp_ms: sec as u64 * 1000 + nsec as u64 / 1_000_000,
+            pid,
+            tid,
+            uid,
+            tag_id,
+            tag: tag.map(|tag| tag.name.clone()),
+            fields,
+        });
+    }
+    entries
+}
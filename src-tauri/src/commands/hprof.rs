@@ -0,0 +1,294 @@
+//! Conversion of Android heap dumps to standard HPROF, as `hprof-conv` does.
+//!
+//! ART writes "JAVA PROFILE 1.0.3" files with extra heap dump sub-records.
+//! Desktop tools only understand 1.0.2, so the Android-only roots become
+//! `ROOT_UNKNOWN`, array stubs without data become zero-filled primitive
+//! arrays, and heap info markers are dropped.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+const STANDARD_HEADER: &[u8] = b"JAVA PROFILE 1.0.2\0";
+
+const TAG_HEAP_DUMP: u8 = 0x0c;
+const TAG_HEAP_DUMP_SEGMENT: u8 = 0x1c;
+
+// Standard heap dump sub-records
+const ROOT_UNKNOWN: u8 = 0xff;
+const ROOT_JNI_GLOBAL: u8 = 0x01;
+const ROOT_JNI_LOCAL: u8 = 0x02;
+const ROOT_JAVA_FRAME: u8 = 0x03;
+const ROOT_NATIVE_STACK: u8 = 0x04;
+const ROOT_STICKY_CLASS: u8 = 0x05;
+const ROOT_THREAD_BLOCK: u8 = 0x06;
+const ROOT_MONITOR_USED: u8 = 0x07;
+const ROOT_THREAD_OBJECT: u8 = 0x08;
+const CLASS_DUMP: u8 = 0x20;
+const INSTANCE_DUMP: u8 = 0x21;
+const OBJECT_ARRAY_DUMP: u8 = 0x22;
+const PRIMITIVE_ARRAY_DUMP: u8 = 0x23;
+
+// Android additions
+const ROOT_INTERNED_STRING: u8 = 0x89;
+const ROOT_FINALIZING: u8 = 0x8a;
+const ROOT_DEBUGGER: u8 = 0x8b;
+const ROOT_REFERENCE_CLEANUP: u8 = 0x8c;
+const ROOT_VM_INTERNAL: u8 = 0x8d;
+const ROOT_JNI_MONITOR: u8 = 0x8e;
+const ROOT_UNREACHABLE: u8 = 0x90;
+const PRIMITIVE_ARRAY_NODATA_DUMP: u8 = 0xc3;
+const HEAP_DUMP_INFO: u8 = 0xfe;
+
+/// Size of a value of the given basic type; type 2 (object) is an id.
+fn type_size(basic_type: u8, id_size: usize) -> Result<usize, String> {
+    match basic_type {
+        2 => Ok(id_size),
+        4 | 8 => Ok(1),
+        5 | 9 => Ok(2),
+        6 | 10 => Ok(4),
+        7 | 11 => Ok(8),
+        other => Err(format!("Unknown HPROF basic type {}", other)),
+    }
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    id_size: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or("Truncated heap dump record")?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn skip_ids(&mut self, count: usize) -> Result<(), String> {
+        self.take(count * self.id_size).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn skip_class_dump(&mut self) -> Result<(), String> {
+        // class, stack serial, super, loader, signers, protection domain,
+        // two reserved ids, instance size
+        self.skip_ids(1)?;
+        self.take(4)?;
+        self.skip_ids(6)?;
+        self.take(4)?;
+
+        for _ in 0..self.u16()? {
+            self.take(2)?;
+            let basic_type = self.u8()?;
+            self.take(type_size(basic_type, self.id_size)?)?;
+        }
+        for _ in 0..self.u16()? {
+            self.skip_ids(1)?;
+            let basic_type = self.u8()?;
+            self.take(type_size(basic_type, self.id_size)?)?;
+        }
+        for _ in 0..self.u16()? {
+            self.skip_ids(1)?;
+            self.u8()?;
+        }
+        Ok(())
+    }
+}
+
+/// Rewrites the body of a `HEAP_DUMP`/`HEAP_DUMP_SEGMENT` record.
+fn convert_heap_dump(body: &[u8], id_size: usize) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(body.len());
+    let mut cursor = Cursor {
+        data: body,
+        pos: 0,
+        id_size,
+    };
+
+    while cursor.pos < body.len() {
+        let start = cursor.pos;
+        let tag = cursor.u8()?;
+        match tag {
+            ROOT_INTERNED_STRING
+            | ROOT_FINALIZING
+            | ROOT_DEBUGGER
+            | ROOT_REFERENCE_CLEANUP
+            | ROOT_VM_INTERNAL
+            | ROOT_UNREACHABLE => {
+                let id = cursor.take(id_size)?;
+                out.push(ROOT_UNKNOWN);
+                out.extend_from_slice(id);
+                continue;
+            }
+            ROOT_JNI_MONITOR => {
+                let id = cursor.take(id_size)?;
+                cursor.take(8)?;
+                out.push(ROOT_UNKNOWN);
+                out.extend_from_slice(id);
+                continue;
+            }
+            PRIMITIVE_ARRAY_NODATA_DUMP => {
+                let header = cursor.take(id_size + 8)?;
+                let basic_type = cursor.u8()?;
+                let count = u32::from_be_bytes([
+                    header[id_size + 4],
+                    header[id_size + 5],
+                    header[id_size + 6],
+                    header[id_size + 7],
+                ]) as usize;
+                out.push(PRIMITIVE_ARRAY_DUMP);
+                out.extend_from_slice(header);
+                out.push(basic_type);
+                // The zeroed elements must still fit the u32 segment length
+                let len = count
+                    .checked_mul(type_size(basic_type, id_size)?)
+                    .and_then(|size| size.checked_add(out.len()))
+                    .filter(|&len| len <= u32::MAX as usize)
+                    .ok_or_else(|| "Converted heap dump segment is too large".to_string())?;
+                out.resize(len, 0);
+                continue;
+            }
+            HEAP_DUMP_INFO => {
+                cursor.take(4)?;
+                cursor.skip_ids(1)?;
+                continue;
+            }
+            ROOT_UNKNOWN | ROOT_STICKY_CLASS | ROOT_MONITOR_USED => cursor.skip_ids(1)?,
+            ROOT_JNI_GLOBAL => cursor.skip_ids(2)?,
+            ROOT_JNI_LOCAL | ROOT_JAVA_FRAME | ROOT_THREAD_OBJECT => {
+                cursor.skip_ids(1)?;
+                cursor.take(8)?;
+            }
+            ROOT_NATIVE_STACK | ROOT_THREAD_BLOCK => {
+                cursor.skip_ids(1)?;
+                cursor.take(4)?;
+            }
+            CLASS_DUMP => cursor.skip_class_dump()?,
+            INSTANCE_DUMP => {
+                cursor.skip_ids(1)?;
+                cursor.take(4)?;
+                cursor.skip_ids(1)?;
+                let len = cursor.u32()? as usize;
+                cursor.take(len)?;
+            }
+            OBJECT_ARRAY_DUMP => {
+                cursor.skip_ids(1)?;
+                cursor.take(4)?;
+                let count = cursor.u32()? as usize;
+                cursor.skip_ids(1 + count)?;
+            }
+            PRIMITIVE_ARRAY_DUMP => {
+                cursor.skip_ids(1)?;
+                cursor.take(4)?;
+                let count = cursor.u32()? as usize;
+                let basic_type = cursor.u8()?;
+                cursor.take(count * type_size(basic_type, id_size)?)?;
+            }
+            other => {
+                return Err(format!(
+                    "Unknown heap dump sub-record 0x{:02x} at offset {}",
+                    other, start
+                ))
+            }
+        }
+        // Standard sub-records are copied unchanged
+        out.extend_from_slice(&body[start..cursor.pos]);
+    }
+    Ok(out)
+}
+
+fn read_error(e: io::Error) -> String {
+    format!("Failed to read heap dump: {}", e)
+}
+
+fn write_error(e: io::Error) -> String {
+    format!("Failed to write heap dump: {}", e)
+}
+
+fn convert<R: Read, W: Write>(mut input: R, mut output: W) -> Result<(), String> {
+    // Null-terminated format name, then id size and timestamp
+    let mut name = Vec::new();
+    loop {
+        let mut byte = [0u8];
+        input.read_exact(&mut byte).map_err(read_error)?;
+        if byte[0] == 0 {
+            break;
+        }
+        name.push(byte[0]);
+        if name.len() > 64 {
+            return Err("Not an HPROF file".to_string());
+        }
+    }
+    if !name.starts_with(b"JAVA PROFILE ") {
+        return Err("Not an HPROF file".to_string());
+    }
+    let mut rest = [0u8; 12];
+    input.read_exact(&mut rest).map_err(read_error)?;
+    let id_size = u32::from_be_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+    if !matches!(id_size, 4 | 8) {
+        return Err(format!("Unsupported HPROF id size {}", id_size));
+    }
+    output.write_all(STANDARD_HEADER).map_err(write_error)?;
+    output.write_all(&rest).map_err(write_error)?;
+
+    loop {
+        // tag, time delta, body length
+        let mut header = [0u8; 9];
+        match input.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(read_error(e)),
+        }
+        let tag = header[0];
+        let len = u32::from_be_bytes([header[5], header[6], header[7], header[8]]);
+
+        if tag == TAG_HEAP_DUMP || tag == TAG_HEAP_DUMP_SEGMENT {
+            let mut body = vec![0u8; len as usize];
+            input.read_exact(&mut body).map_err(read_error)?;
+            let body = convert_heap_dump(&body, id_size)?;
+            let new_len = u32::try_from(body.len())
+                .map_err(|_| "Converted heap dump segment is too large".to_string())?;
+            header[5..9].copy_from_slice(&new_len.to_be_bytes());
+            output.write_all(&header).map_err(write_error)?;
+            output.write_all(&body).map_err(write_error)?;
+        } else {
+            output.write_all(&header).map_err(write_error)?;
+            let copied =
+                io::copy(&mut (&mut input).take(len as u64), &mut output).map_err(read_error)?;
+            if copied != len as u64 {
+                return Err("Truncated HPROF record".to_string());
+            }
+        }
+    }
+
+    output.flush().map_err(write_error)
+}
+
+/// Converts the Android heap dump at `input` to a standard HPROF file at
+/// `output`.
+pub fn convert_android_hprof(input: &Path, output: &Path) -> Result<(), String> {
+    let reader = BufReader::new(
+        File::open(input).map_err(|e| format!("Failed to open {}: {}", input.display(), e))?,
+    );
+    let writer = BufWriter::new(
+        File::create(output)
+            .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?,
+    );
+    convert(reader, writer)
+}
@@ -0,0 +1,402 @@
+use crate::commands::utils::{app_data_subdir, file_safe_name, resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// Which `am` subcommand delivers the intent.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IntentTarget {
+    Activity,
+    Service,
+    ForegroundService,
+    Broadcast,
+}
+
+/// A typed extra, serialised as `{ "type": "int", "value": 3 }`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ExtraValue {
+    String(String),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Bool(bool),
+    Uri(String),
+    Component(String),
+    StringArray(Vec<String>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    FloatArray(Vec<f32>),
+    Null,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntentExtra {
+    pub key: String,
+    pub value: ExtraValue,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct IntentSpec {
+    pub action: Option<String>,
+    pub data: Option<String>,
+    pub mime_type: Option<String>,
+    /// `com.example.app/.DetailActivity`
+    pub component: Option<String>,
+    /// Restricts resolution to one package when no component is given.
+    pub package: Option<String>,
+    pub categories: Vec<String>,
+    /// `Intent` flag names (`FLAG_ACTIVITY_NEW_TASK`, `ACTIVITY_CLEAR_TOP`) or
+    /// raw values (`0x10000000`).
+    pub flags: Vec<String>,
+    pub extras: Vec<IntentExtra>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SendIntentOptions {
+    /// `am start -W`: wait for the launch to finish and report timings.
+    pub wait: bool,
+    /// `am start -S`: force-stop the app first, for cold start measurements.
+    pub force_stop: bool,
+    pub user_id: Option<u32>,
+}
+
+/// Parsed `am start -W` output.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LaunchTiming {
+    pub status: Option<String>,
+    /// `COLD`, `WARM`, `HOT` (Android 10+).
+    pub launch_state: Option<String>,
+    pub activity: Option<String>,
+    pub this_time_ms: Option<u64>,
+    pub total_time_ms: Option<u64>,
+    pub wait_time_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IntentResult {
+    pub success: bool,
+    pub output: String,
+    pub timing: Option<LaunchTiming>,
+    pub broadcast_result: Option<i32>,
+    pub broadcast_data: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedIntent {
+    pub name: String,
+    pub package_id: String,
+    pub target: IntentTarget,
+    pub intent: IntentSpec,
+    /// Milliseconds since the Unix epoch.
+    #[serde(default)]
+    pub saved_at: u64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const INTENT_FLAGS: &[(&str, u32)] = &[
+    ("GRANT_READ_URI_PERMISSION", 0x0000_0001),
+    ("GRANT_WRITE_URI_PERMISSION", 0x0000_0002),
+    ("DEBUG_LOG_RESOLUTION", 0x0000_0008),
+    ("INCLUDE_STOPPED_PACKAGES", 0x0000_0020),
+    ("ACTIVITY_CLEAR_TASK", 0x0000_8000),
+    ("ACTIVITY_NO_ANIMATION", 0x0001_0000),
+    ("ACTIVITY_REORDER_TO_FRONT", 0x0002_0000),
+    ("ACTIVITY_NEW_DOCUMENT", 0x0008_0000),
+    ("ACTIVITY_RESET_TASK_IF_NEEDED", 0x0020_0000),
+    ("ACTIVITY_EXCLUDE_FROM_RECENTS", 0x0080_0000),
+    ("ACTIVITY_CLEAR_TOP", 0x0400_0000),
+    ("ACTIVITY_MULTIPLE_TASK", 0x0800_0000),
+    ("ACTIVITY_NEW_TASK", 0x1000_0000),
+    ("ACTIVITY_SINGLE_TOP", 0x2000_0000),
+    ("ACTIVITY_NO_HISTORY", 0x4000_0000),
+    ("RECEIVER_REGISTERED_ONLY", 0x4000_0000),
+    ("RECEIVER_REPLACE_PENDING", 0x2000_0000),
+    ("RECEIVER_FOREGROUND", 0x1000_0000),
+];
+
+fn parse_flag(flag: &str) -> Result<u32, String> {
+    let flag = flag.trim();
+    if let Some(hex) = flag.strip_prefix("0x").or_else(|| flag.strip_prefix("0X")) {
+        return u32::from_str_radix(hex, 16).map_err(|_| format!("Invalid flag: {}", flag));
+    }
+    if let Ok(value) = flag.parse::<u32>() {
+        return Ok(value);
+    }
+
+    let name = flag.strip_prefix("FLAG_").unwrap_or(flag);
+    INTENT_FLAGS
+        .iter()
+        .find(|(known, _)| known.eq_ignore_ascii_case(name))
+        .map(|(_, value)| *value)
+        .ok_or_else(|| format!("Unknown intent flag: {}", flag))
+}
+
+/// Array extras are comma separated; commas inside values are escaped.
+fn join_array<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string().replace(',', "\\,"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn extra_args(extra: &IntentExtra) -> Vec<String> {
+    let key = shell_quote(&extra.key);
+    let (option, value) = match &extra.value {
+        ExtraValue::String(v) => ("--es", v.clone()),
+        ExtraValue::Int(v) => ("--ei", v.to_string()),
+        ExtraValue::Long(v) => ("--el", v.to_string()),
+        ExtraValue::Float(v) => ("--ef", v.to_string()),
+        ExtraValue::Bool(v) => ("--ez", v.to_string()),
+        ExtraValue::Uri(v) => ("--eu", v.clone()),
+        ExtraValue::Component(v) => ("--ecn", v.clone()),
+        ExtraValue::StringArray(v) => ("--esa", join_array(v)),
+        ExtraValue::IntArray(v) => ("--eia", join_array(v)),
+        ExtraValue::LongArray(v) => ("--ela", join_array(v)),
+        ExtraValue::FloatArray(v) => ("--efa", join_array(v)),
+        ExtraValue::Null => return vec!["--esn".to_string(), key],
+    };
+    vec![option.to_string(), key, shell_quote(&value)]
+}
+
+/// Builds the `am` argument list for `intent`. User-supplied values are
+/// quoted because `adb shell` re-parses the joined command line.
+fn build_am_args(
+    target: IntentTarget,
+    intent: &IntentSpec,
+    options: &SendIntentOptions,
+) -> Result<Vec<String>, String> {
+    let command = match target {
+        IntentTarget::Activity => "start",
+        IntentTarget::Service => "startservice",
+        IntentTarget::ForegroundService => "start-foreground-service",
+        IntentTarget::Broadcast => "broadcast",
+    };
+    let mut args = vec!["am".to_string(), command.to_string()];
+
+    if target == IntentTarget::Activity {
+        if options.wait {
+            args.push("-W".to_string());
+        }
+        if options.force_stop {
+            args.push("-S".to_string());
+        }
+    }
+    if let Some(user) = options.user_id {
+        args.extend(["--user".to_string(), user.to_string()]);
+    }
+
+    if let Some(action) = &intent.action {
+        args.extend(["-a".to_string(), shell_quote(action)]);
+    }
+    if let Some(data) = &intent.data {
+        args.extend(["-d".to_string(), shell_quote(data)]);
+    }
+    if let Some(mime_type) = &intent.mime_type {
+        args.extend(["-t".to_string(), shell_quote(mime_type)]);
+    }
+    for category in &intent.categories {
+        args.extend(["-c".to_string(), shell_quote(category)]);
+    }
+    if !intent.flags.is_empty() {
+        let mut flags = 0u32;
+        for flag in &intent.flags {
+            flags |= parse_flag(flag)?;
+        }
+        args.extend(["-f".to_string(), format!("0x{:08x}", flags)]);
+    }
+    for extra in &intent.extras {
+        args.extend(extra_args(extra));
+    }
+
+    // The component (or package) must come last.
+    if let Some(component) = &intent.component {
+        args.extend(["-n".to_string(), shell_quote(component)]);
+    } else if let Some(package) = &intent.package {
+        args.push(shell_quote(package));
+    }
+
+    Ok(args)
+}
+
+fn parse_launch_timing(output: &str) -> LaunchTiming {
+    let mut timing = LaunchTiming::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        let value = value.trim();
+        match key {
+            "Status" => timing.status = Some(value.to_string()),
+            "LaunchState" => timing.launch_state = Some(value.to_string()),
+            "Activity" => timing.activity = Some(value.to_string()),
+            "ThisTime" => timing.this_time_ms = value.parse().ok(),
+            "TotalTime" => timing.total_time_ms = value.parse().ok(),
+            "WaitTime" => timing.wait_time_ms = value.parse().ok(),
+            _ => {}
+        }
+    }
+    timing
+}
+
+/// `Broadcast completed: result=0, data="some data"`
+fn parse_broadcast_result(output: &str) -> (Option<i32>, Option<String>) {
+    let Some(line) = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Broadcast completed: "))
+    else {
+        return (None, None);
+    };
+
+    let code = line
+        .strip_prefix("result=")
+        .and_then(|rest| rest.split(|c: char| c == ',' || c.is_whitespace()).next())
+        .and_then(|code| code.parse().ok());
+    let data = line
+        .split_once("data=\"")
+        .and_then(|(_, rest)| rest.rsplit_once('"'))
+        .map(|(data, _)| data.to_string());
+    (code, data)
+}
+
+fn saved_intents_path(app: &AppHandle, package: &str) -> Result<PathBuf, String> {
+    Ok(app_data_subdir(app, "intents")?.join(format!("{}.json", file_safe_name(package))))
+}
+
+fn load_saved_intents(app: &AppHandle, package: &str) -> Result<Vec<SavedIntent>, String> {
+    let path = saved_intents_path(app, package)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read saved intents: {}", e))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Corrupt saved intents: {}", e))
+}
+
+fn store_saved_intents(
+    app: &AppHandle,
+    package: &str,
+    intents: &[SavedIntent],
+) -> Result<(), String> {
+    let path = saved_intents_path(app, package)?;
+    let bytes = serde_json::to_vec_pretty(intents).map_err(|e| e.to_string())?;
+    fs::write(&path, bytes).map_err(|e| format!("Failed to save intents: {}", e))
+}
+
+/// Runs `am` for `intent` and parses what it printed. Shared with the deep
+/// link tester.
+pub(crate) fn run_intent(
+    adb_path: &str,
+    device: &str,
+    target: IntentTarget,
+    intent: &IntentSpec,
+    options: &SendIntentOptions,
+) -> Result<IntentResult, String> {
+    let args = build_am_args(target, intent, options)?;
+
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell"])
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .trim()
+    .to_string();
+    // `am` exits 0 even when nothing resolved; failures show up as "Error".
+    let success = output.status.success()
+        && !text
+            .lines()
+            .any(|line| line.trim_start().starts_with("Error"));
+
+    let timing =
+        (target == IntentTarget::Activity && options.wait).then(|| parse_launch_timing(&text));
+    let (broadcast_result, broadcast_data) = if target == IntentTarget::Broadcast {
+        parse_broadcast_result(&text)
+    } else {
+        (None, None)
+    };
+
+    Ok(IntentResult {
+        success,
+        output: text,
+        timing,
+        broadcast_result,
+        broadcast_data,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Sends an intent through `am start`, `am startservice`,
+/// `am start-foreground-service` or `am broadcast`.
+#[tauri::command]
+pub async fn send_intent(
+    app: AppHandle,
+    device: String,
+    target: IntentTarget,
+    intent: IntentSpec,
+    options: Option<SendIntentOptions>,
+) -> Result<IntentResult, String> {
+    let adb_path = resolve_adb_path(&app);
+    let options = options.unwrap_or_default();
+    run_intent(&adb_path, &device, target, &intent, &options)
+}
+
+/// Saves (or replaces, by name) an intent for its package.
+#[tauri::command]
+pub async fn save_intent(app: AppHandle, intent: SavedIntent) -> Result<(), String> {
+    let mut intent = intent;
+    intent.saved_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let package = intent.package_id.clone();
+    let mut saved = load_saved_intents(&app, &package)?;
+    saved.retain(|s| s.name != intent.name);
+    saved.push(intent);
+    store_saved_intents(&app, &package, &saved)
+}
+
+#[tauri::command]
+pub async fn list_saved_intents(
+    app: AppHandle,
+    package: String,
+) -> Result<Vec<SavedIntent>, String> {
+    load_saved_intents(&app, &package)
+}
+
+#[tauri::command]
+pub async fn delete_saved_intent(
+    app: AppHandle,
+    package: String,
+    name: String,
+) -> Result<(), String> {
+    let mut saved = load_saved_intents(&app, &package)?;
+    let before = saved.len();
+    saved.retain(|s| s.name != name);
+    if saved.len() == before {
+        return Err(format!("No saved intent named {}", name));
+    }
+    store_saved_intents(&app, &package, &saved)
+}
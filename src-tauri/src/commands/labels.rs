@@ -0,0 +1,364 @@
+use crate::commands::apk_info::{choose_icon_path, resolve_label, MAX_ZIP_ENTRY_SIZE};
+use crate::commands::axml::{parse_xml, ResourceTable};
+use crate::commands::packages::parse_package_line;
+use crate::commands::utils::{app_cache_subdir, file_safe_name, resolve_adb_path, shell_quote};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use flate2::read::DeflateDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Emitter};
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageLabel {
+    pub package_id: String,
+    pub version_code: Option<u64>,
+    pub label: Option<String>,
+    /// Launcher icon as base64 (PNG or WebP), when a raster rendition exists.
+    pub icon: Option<String>,
+    pub icon_path: Option<String>,
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// Remote APK Access
+// ============================================================================
+
+/// Bytes read from the end of the APK in the first round trip: the largest
+/// possible end of central directory record plus its comment.
+const TAIL_READ: u64 = 65_557;
+/// Extra bytes read after a local header, to cover its extra field.
+const LOCAL_HEADER_SLACK: u64 = 1024;
+
+struct ZipEntryInfo {
+    method: u16,
+    compressed_size: u64,
+    local_offset: u64,
+}
+
+/// Reads single entries of an APK on the device without pulling it, using
+/// `tail -c +N | head -c L` through `adb exec-out`.
+struct RemoteZip<'a> {
+    adb_path: &'a str,
+    device: &'a str,
+    path: String,
+    entries: HashMap<String, ZipEntryInfo>,
+}
+
+fn le_u16(data: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn le_u32(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn exec_out(adb_path: &str, device: &str, script: &str) -> Result<Vec<u8>, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "exec-out", script])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(output.stdout)
+}
+
+impl<'a> RemoteZip<'a> {
+    fn open(adb_path: &'a str, device: &'a str, path: &str) -> Result<Self, String> {
+        let quoted = shell_quote(path);
+        // File size and the tail of the file in one round trip.
+        let output = exec_out(
+            adb_path,
+            device,
+            &format!("stat -c %s {0} && tail -c {1} {0}", quoted, TAIL_READ),
+        )?;
+        let newline = output
+            .iter()
+            .position(|b| *b == b'\n')
+            .ok_or("Failed to read APK size")?;
+        let size: u64 = String::from_utf8_lossy(&output[..newline])
+            .trim()
+            .parse()
+            .map_err(|_| format!("Cannot read {}", path))?;
+        let tail = &output[newline + 1..];
+        let tail_start = size.saturating_sub(tail.len() as u64);
+
+        // End of central directory record, searched from the end.
+        let eocd = (0..tail.len().saturating_sub(21))
+            .rev()
+            .find(|&i| tail[i..].starts_with(&[0x50, 0x4b, 0x05, 0x06]))
+            .ok_or("Not a zip file")?;
+        let cd_size = le_u32(tail, eocd + 12).ok_or("Truncated zip")? as u64;
+        let cd_offset = le_u32(tail, eocd + 16).ok_or("Truncated zip")? as u64;
+
+        let mut zip = RemoteZip {
+            adb_path,
+            device,
+            path: quoted,
+            entries: HashMap::new(),
+        };
+        let directory = if cd_offset >= tail_start {
+            let start = (cd_offset - tail_start) as usize;
+            tail.get(start..start + cd_size as usize)
+                .ok_or("Truncated central directory")?
+                .to_vec()
+        } else {
+            zip.read_range(cd_offset, cd_size)?
+        };
+        zip.entries = Self::parse_central_directory(&directory);
+        Ok(zip)
+    }
+
+    fn parse_central_directory(directory: &[u8]) -> HashMap<String, ZipEntryInfo> {
+        let mut entries = HashMap::new();
+        let mut pos = 0;
+        while le_u32(directory, pos) == Some(0x0201_4b50) {
+            let (Some(method), Some(compressed), Some(name_len), Some(extra_len)) = (
+                le_u16(directory, pos + 10),
+                le_u32(directory, pos + 20),
+                le_u16(directory, pos + 28),
+                le_u16(directory, pos + 30),
+            ) else {
+                break;
+            };
+            let (Some(comment_len), Some(offset)) =
+                (le_u16(directory, pos + 32), le_u32(directory, pos + 42))
+            else {
+                break;
+            };
+            let name_start = pos + 46;
+            let Some(name) = directory.get(name_start..name_start + name_len as usize) else {
+                break;
+            };
+            entries.insert(
+                String::from_utf8_lossy(name).to_string(),
+                ZipEntryInfo {
+                    method,
+                    compressed_size: compressed as u64,
+                    local_offset: offset as u64,
+                },
+            );
+            pos = name_start + name_len as usize + extra_len as usize + comment_len as usize;
+        }
+        entries
+    }
+
+    fn read_range(&self, offset: u64, len: u64) -> Result<Vec<u8>, String> {
+        exec_out(
+            self.adb_path,
+            self.device,
+            &format!("tail -c +{} {} | head -c {}", offset + 1, self.path, len),
+        )
+    }
+
+    fn read(&self, name: &str) -> Result<Option<Vec<u8>>, String> {
+        let Some(entry) = self.entries.get(name) else {
+            return Ok(None);
+        };
+        if entry.compressed_size > MAX_ZIP_ENTRY_SIZE {
+            return Err(format!("{} is too large", name));
+        }
+
+        let wanted = 30 + name.len() as u64 + entry.compressed_size + LOCAL_HEADER_SLACK;
+        let mut data = self.read_range(entry.local_offset, wanted)?;
+        if le_u32(&data, 0) != Some(0x0403_4b50) {
+            return Err(format!("Bad local header for {}", name));
+        }
+        let name_len = le_u16(&data, 26).unwrap_or(0) as u64;
+        let extra_len = le_u16(&data, 28).unwrap_or(0) as u64;
+        let data_start = 30 + name_len + extra_len;
+        if data_start + entry.compressed_size > data.len() as u64 {
+            // Unusually large extra field (e.g. page alignment padding)
+            data = self.read_range(entry.local_offset, data_start + entry.compressed_size)?;
+        }
+        let compressed = data
+            .get(data_start as usize..(data_start + entry.compressed_size) as usize)
+            .ok_or_else(|| format!("Truncated entry {}", name))?;
+
+        match entry.method {
+            0 => Ok(Some(compressed.to_vec())),
+            8 => {
+                let mut out = Vec::new();
+                DeflateDecoder::new(compressed)
+                    .take(MAX_ZIP_ENTRY_SIZE + 1)
+                    .read_to_end(&mut out)
+                    .map_err(|e| format!("Failed to inflate {}: {}", name, e))?;
+                if out.len() as u64 > MAX_ZIP_ENTRY_SIZE {
+                    return Err(format!("{} is too large", name));
+                }
+                Ok(Some(out))
+            }
+            method => Err(format!("Unsupported compression {} for {}", method, name)),
+        }
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// `None` when the version code is unknown (Android 8 and older): such an
+/// entry could never be told apart from the next update, so it is not cached.
+fn cache_path(dir: &std::path::Path, package: &str, version_code: Option<u64>) -> Option<PathBuf> {
+    let version_code = version_code?;
+    Some(dir.join(format!("{}_{}.json", file_safe_name(package), version_code)))
+}
+
+fn resolve_label_and_icon(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    apk_path: &str,
+    version_code: Option<u64>,
+) -> Result<PackageLabel, String> {
+    let zip = RemoteZip::open(adb_path, device, apk_path)?;
+    let manifest = parse_xml(
+        &zip.read("AndroidManifest.xml")?
+            .ok_or("APK has no AndroidManifest.xml")?,
+    )?;
+    let table = zip
+        .read("resources.arsc")?
+        .and_then(|bytes| ResourceTable::parse(&bytes).ok());
+
+    let application = manifest.children_named("application").next();
+    let label = application.and_then(|a| resolve_label(a.attr("label"), table.as_ref()));
+    let icon_attr = application.and_then(|a| a.attr("icon").or_else(|| a.attr("roundIcon")));
+
+    let (icon_path, icon) = match choose_icon_path(table.as_ref(), icon_attr) {
+        Some((path, true)) => {
+            let bytes = zip.read(&path)?;
+            (Some(path), bytes.map(|b| BASE64.encode(b)))
+        }
+        Some((path, false)) => (Some(path), None),
+        None => (None, None),
+    };
+
+    Ok(PackageLabel {
+        package_id: package.to_string(),
+        version_code,
+        label,
+        icon,
+        icon_path,
+        error: None,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Resolves labels and launcher icons for `packages`. Only the manifest,
+/// resource table and icon are read from each base APK. Results are cached
+/// per package and version code; every resolved package is also reported as
+/// a `package-label` event so the list can fill in progressively.
+#[tauri::command]
+pub async fn get_package_labels(
+    app: AppHandle,
+    device: String,
+    packages: Vec<String>,
+) -> Result<Vec<PackageLabel>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let cache_dir = app_cache_subdir(&app, "labels")?;
+
+    let output = Command::new(&adb_path)
+        .args([
+            "-s",
+            &device,
+            "shell",
+            "pm list packages -f --show-versioncode 2>/dev/null || pm list packages -f",
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let installed: HashMap<String, (String, Option<u64>)> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(parse_package_line)
+        .map(|p| (p.package_id, (p.path, p.version_code)))
+        .collect();
+
+    let mut results: Vec<Option<PackageLabel>> = Vec::with_capacity(packages.len());
+    let mut pending = Vec::new();
+    for (index, package) in packages.iter().enumerate() {
+        let Some((path, version_code)) = installed.get(package) else {
+            results.push(Some(PackageLabel {
+                package_id: package.clone(),
+                version_code: None,
+                label: None,
+                icon: None,
+                icon_path: None,
+                error: Some("Package not installed".to_string()),
+            }));
+            continue;
+        };
+        let cached = cache_path(&cache_dir, package, *version_code)
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice::<PackageLabel>(&bytes).ok());
+        if cached.is_none() {
+            pending.push((index, package.clone(), path.clone(), *version_code));
+        }
+        results.push(cached);
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(results);
+    let workers = pending.len().clamp(1, 4);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some((index, package, path, version_code)) =
+                    pending.get(next.fetch_add(1, Ordering::SeqCst))
+                {
+                    let label = match resolve_label_and_icon(
+                        &adb_path,
+                        &device,
+                        package,
+                        path,
+                        *version_code,
+                    ) {
+                        Ok(label) => {
+                            if let (Some(path), Ok(bytes)) = (
+                                cache_path(&cache_dir, package, *version_code),
+                                serde_json::to_vec(&label),
+                            ) {
+                                let _ = fs::write(path, bytes);
+                            }
+                            label
+                        }
+                        // Not cached, so the next request tries again
+                        Err(e) => PackageLabel {
+                            package_id: package.clone(),
+                            version_code: *version_code,
+                            label: None,
+                            icon: None,
+                            icon_path: None,
+                            error: Some(e),
+                        },
+                    };
+
+                    let _ = app.emit("package-label", label.clone());
+                    if let Ok(mut results) = results.lock() {
+                        results[*index] = Some(label);
+                    }
+                }
+            });
+        }
+    });
+
+    Ok(results
+        .into_inner()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flatten()
+        .collect())
+}
@@ -1,20 +1,1094 @@
-use crate::commands::utils::resolve_adb_path;
-use std::process::Command;
-use tauri::AppHandle;
+use crate::commands::event_log::{decode_event_log, parse_event_log_tags, EventLogEntry};
+use crate::commands::utils::{device_online, resolve_adb_path};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
 
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Verbose,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+/// Ring buffers logcat can read with `-b`. Without any, logcat reads
+/// `main`, `system` and `crash`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogBuffer {
+    Main,
+    System,
+    Crash,
+    Radio,
+    Events,
+    Security,
+    Kernel,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogBufferInfo {
+    pub buffer: LogBuffer,
+    pub size_bytes: Option<u64>,
+    pub consumed_bytes: Option<u64>,
+    /// Largest single entry the buffer accepts.
+    pub max_entry_bytes: Option<u64>,
+    /// Set when the device has no such buffer or shell may not read it.
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogEntry {
+    /// Milliseconds since the Unix epoch, device clock.
+    pub timestamp_ms: u64,
+    pub pid: u32,
+    pub tid: u32,
+    /// `None` on devices whose logcat cannot print UIDs (Android 6 and older).
+    pub uid: Option<u32>,
+    pub level: LogLevel,
+    pub tag: String,
+    pub message: String,
+    /// Buffer the entry was read from, when logcat announced it.
+    pub buffer: Option<LogBuffer>,
+}
+
+/// Which entries a session sends to the frontend. Empty lists and `None`
+/// fields do not filter.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LogFilter {
+    pub min_level: Option<LogLevel>,
+    /// Only entries with one of these tags (exact match).
+    pub include_tags: Vec<String>,
+    pub exclude_tags: Vec<String>,
+    /// Only entries from these processes, or from any process of `packages`.
+    pub pids: Vec<u32>,
+    /// Followed across restarts and `:service` processes.
+    pub packages: Vec<String>,
+    pub message_regex: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LogcatOptions {
+    /// Number of recent lines to print before following. Defaults to 500.
+    pub tail: Option<u32>,
+    /// Buffers to follow; logcat's defaults when `None` or empty.
+    pub buffers: Option<Vec<LogBuffer>>,
+    pub filter: Option<LogFilter>,
+}
+
+/// Payload of the `logcat-entries` event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogcatBatch {
+    pub session_id: u64,
+    pub entries: Vec<LogEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LogcatState {
+    Running,
+    Paused,
+    /// The device went away; the session resumes once it is back.
+    Reconnecting,
+    /// Stopped by `stop_logcat`, or because logcat failed while the device
+    /// stayed online; `message` then carries logcat's error.
+    Stopped,
+}
+
+/// Payload of the `logcat-status` event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LogcatStatus {
+    pub session_id: u64,
+    pub state: LogcatState,
+    pub message: Option<String>,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+const DEFAULT_TAIL: u32 = 500;
+/// Entries are emitted when this many are pending or `BATCH_INTERVAL` passed.
+const MAX_BATCH: usize = 500;
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+struct CompiledFilter {
+    spec: LogFilter,
+    regex: Option<Regex>,
+}
+
+struct LogcatSession {
+    stopped: AtomicBool,
+    paused: AtomicBool,
+    /// Set when the filter changed and logcat must restart with it.
+    restart: AtomicBool,
+    filter: Mutex<Arc<CompiledFilter>>,
+    /// Current PIDs of the packages in the filter.
+    package_pids: Mutex<HashSet<u32>>,
+    /// The running `logcat` or `wait-for-device` child, killed on pause/stop.
+    child: Mutex<Option<Child>>,
+    /// `logcat -b events` child that follows process starts for package filters.
+    watcher: Mutex<Option<Child>>,
+}
+
+impl LogcatSession {
+    fn kill_child(&self) {
+        for slot in [&self.child, &self.watcher] {
+            if let Ok(mut child) = slot.lock() {
+                if let Some(child) = child.as_mut() {
+                    let _ = child.kill();
+                }
+            }
+        }
+    }
+
+    fn interrupted(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+            || self.paused.load(Ordering::SeqCst)
+            || self.restart.load(Ordering::SeqCst)
+    }
+
+    /// Stores `child` in `slot` unless the session was paused, stopped or
+    /// restarted meanwhile.
+    fn store_child(&self, slot: &Mutex<Option<Child>>, mut child: Child) -> bool {
+        let Ok(mut slot) = slot.lock() else {
+            return false;
+        };
+        if self.interrupted() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return false;
+        }
+        *slot = Some(child);
+        true
+    }
+
+    fn set_child(&self, child: Child) -> bool {
+        self.store_child(&self.child, child)
+    }
+
+    /// Waits for the child to exit. Polls instead of blocking in `wait` so
+    /// the child stays reachable for `kill_child` meanwhile.
+    fn wait_child(&self) {
+        loop {
+            {
+                let Ok(mut slot) = self.child.lock() else {
+                    return;
+                };
+                let Some(child) = slot.as_mut() else {
+                    return;
+                };
+                if !matches!(child.try_wait(), Ok(None)) {
+                    *slot = None;
+                    return;
+                }
+            }
+            thread::sleep(BATCH_INTERVAL);
+        }
+    }
+
+    fn stop_watcher(&self) {
+        let child = self.watcher.lock().ok().and_then(|mut slot| slot.take());
+        if let Some(mut child) = child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        if let Ok(mut pids) = self.package_pids.lock() {
+            pids.clear();
+        }
+    }
+}
+
+/// Running sessions by id.
+static SESSIONS: Mutex<BTreeMap<u64, Arc<LogcatSession>>> = Mutex::new(BTreeMap::new());
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+fn session(session_id: u64) -> Result<Arc<LogcatSession>, String> {
+    SESSIONS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| format!("No logcat session with id {}", session_id))
+}
+
+impl LogLevel {
+    fn from_letter(letter: &str) -> Option<Self> {
+        match letter {
+            "V" => Some(Self::Verbose),
+            "D" => Some(Self::Debug),
+            "I" => Some(Self::Info),
+            "W" => Some(Self::Warn),
+            "E" => Some(Self::Error),
+            "F" | "A" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    fn letter(self) -> &'static str {
+        match self {
+            Self::Verbose => "V",
+            Self::Debug => "D",
+            Self::Info => "I",
+            Self::Warn => "W",
+            Self::Error => "E",
+            Self::Fatal => "F",
+        }
+    }
+}
+
+const ALL_BUFFERS: [LogBuffer; 7] = [
+    LogBuffer::Main,
+    LogBuffer::System,
+    LogBuffer::Crash,
+    LogBuffer::Radio,
+    LogBuffer::Events,
+    LogBuffer::Security,
+    LogBuffer::Kernel,
+];
+
+/// Smallest and largest ring buffer sizes logd accepts.
+const MIN_BUFFER_SIZE: u64 = 64 * 1024;
+const MAX_BUFFER_SIZE: u64 = 256 * 1024 * 1024;
+
+impl LogBuffer {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Main => "main",
+            Self::System => "system",
+            Self::Crash => "crash",
+            Self::Radio => "radio",
+            Self::Events => "events",
+            Self::Security => "security",
+            Self::Kernel => "kernel",
+        }
+    }
+
+    fn from_str(name: &str) -> Option<Self> {
+        ALL_BUFFERS.into_iter().find(|b| b.as_str() == name)
+    }
+}
+
+/// `-b` arguments for `buffers`.
+fn buffer_args(buffers: &[LogBuffer]) -> Vec<&'static str> {
+    buffers.iter().flat_map(|b| ["-b", b.as_str()]).collect()
+}
+
+/// `--------- beginning of main` or `--------- switch to system`, printed
+/// before entries of another buffer.
+fn parse_buffer_marker(line: &str) -> Option<LogBuffer> {
+    let rest = line.strip_prefix("--------- ")?;
+    let name = rest
+        .strip_prefix("beginning of ")
+        .or_else(|| rest.strip_prefix("switch to "))?;
+    LogBuffer::from_str(name.trim())
+}
+
+/// `256 KiB`, `256Kb`, `5120 B` or `4068b` in bytes.
+fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let value: u64 = text[..digits].parse().ok()?;
+    let multiplier = match text[digits..].trim() {
+        "" | "b" | "B" => 1,
+        unit if unit.starts_with('K') => 1024,
+        unit if unit.starts_with('M') => 1024 * 1024,
+        unit if unit.starts_with('G') => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    Some(value * multiplier)
+}
+
+/// Parses one `logcat -g` line:
+///
+/// ```text
+/// main: ring buffer is 256 KiB (238 KiB consumed, 210 KiB readable), max entry is 5120 B, max payload is 4068 B
+/// main: ring buffer is 256Kb (255Kb consumed), max entry is 5120b, max payload is 4076b
+/// ```
+fn parse_buffer_size(buffer: LogBuffer, line: &str) -> Option<LogBufferInfo> {
+    let rest = line
+        .strip_prefix(buffer.as_str())?
+        .strip_prefix(": ring buffer is ")?;
+    let (size, rest) = rest.split_once('(')?;
+    let consumed = rest.split(" consumed").next()?;
+    let max_entry = rest
+        .split_once("max entry is ")
+        .and_then(|(_, max)| parse_size(max.split(',').next()?));
+    Some(LogBufferInfo {
+        buffer,
+        size_bytes: parse_size(size),
+        consumed_bytes: parse_size(consumed),
+        max_entry_bytes: max_entry,
+        error: None,
+    })
+}
+
+/// Splits the output of one `logcat -g` call per buffer, each preceded by
+/// an `== <buffer>` line.
+fn parse_buffer_sizes(output: &str) -> Vec<LogBufferInfo> {
+    let mut sections: Vec<(LogBuffer, Vec<&str>)> = Vec::new();
+    for line in output.lines() {
+        if let Some(buffer) = line.strip_prefix("== ").and_then(LogBuffer::from_str) {
+            sections.push((buffer, Vec::new()));
+        } else if let Some((_, lines)) = sections.last_mut() {
+            lines.push(line.trim_end());
+        }
+    }
+    sections
+        .into_iter()
+        .map(|(buffer, lines)| {
+            lines
+                .iter()
+                .find_map(|line| parse_buffer_size(buffer, line))
+                .unwrap_or_else(|| LogBufferInfo {
+                    buffer,
+                    size_bytes: None,
+                    consumed_bytes: None,
+                    max_entry_bytes: None,
+                    error: Some(lines.join("\n").trim().to_string())
+                        .filter(|e| !e.is_empty())
+                        .or_else(|| Some("Buffer not available".to_string())),
+                })
+        })
+        .collect()
+}
+
+/// Runs `adb logcat` with `args` and fails with its output when logcat does.
+fn run_logcat(adb_path: &str, device: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "logcat"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    // logcat reached through `adb shell` may exit 0 after printing an error
+    if !output.status.success() || stdout.contains("failed") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}{}", stdout, stderr).trim().to_string());
+    }
+    Ok(stdout)
+}
+
+/// `-v uid` prints names for well-known UIDs and app UIDs as `u0_a123`.
+fn parse_uid(value: &str) -> Option<u32> {
+    if let Ok(uid) = value.parse() {
+        return Some(uid);
+    }
+    if let Some(rest) = value.strip_prefix('u') {
+        let (user, app) = rest.split_once('_')?;
+        let user: u32 = user.parse().ok()?;
+        let base = if let Some(n) = app.strip_prefix('a') {
+            10_000 + n.parse::<u32>().ok()?
+        } else if let Some(n) = app.strip_prefix('i') {
+            99_000 + n.parse::<u32>().ok()?
+        } else {
+            return None;
+        };
+        return Some(user * 100_000 + base);
+    }
+    match value {
+        "root" => Some(0),
+        "system" => Some(1000),
+        "radio" => Some(1001),
+        "bluetooth" => Some(1002),
+        "graphics" => Some(1003),
+        "wifi" => Some(1010),
+        "media" => Some(1013),
+        "nfc" => Some(1027),
+        "shell" => Some(2000),
+        "logd" => Some(1036),
+        "audioserver" => Some(1041),
+        "cameraserver" => Some(1047),
+        "nobody" => Some(9999),
+        _ => None,
+    }
+}
+
+/// `1714564800.123` -> milliseconds
+fn parse_epoch_ms(value: &str) -> Option<u64> {
+    let (secs, fraction) = value.split_once('.').unwrap_or((value, "0"));
+    let millis: String = fraction.chars().chain("000".chars()).take(3).collect();
+    Some(secs.parse::<u64>().ok()? * 1000 + millis.parse::<u64>().ok()?)
+}
+
+fn next_token<'a>(rest: &mut &'a str) -> Option<&'a str> {
+    let trimmed = rest.trim_start();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let end = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+    let (token, remainder) = trimmed.split_at(end);
+    *rest = remainder;
+    Some(token)
+}
+
+/// Parses a `logcat -v epoch -v uid` line:
+///
+/// ```text
+/// 1714564800.123  u0_a123  1234  1250 I ActivityThread: message
+/// ```
+///
+/// The UID column is missing when the device does not support `-v uid`.
+pub(crate) fn parse_log_line(line: &str) -> Option<LogEntry> {
+    let mut rest = line;
+    let timestamp_ms = parse_epoch_ms(next_token(&mut rest)?)?;
+    let first = next_token(&mut rest)?;
+    let second = next_token(&mut rest)?;
+    let third = next_token(&mut rest)?;
+
+    let (uid, pid, tid, level) = match LogLevel::from_letter(third) {
+        Some(level) => (None, first, second, level),
+        None => {
+            let level = LogLevel::from_letter(next_token(&mut rest)?)?;
+            (parse_uid(first), second, third, level)
+        }
+    };
+
+    let rest = rest.strip_prefix(' ').unwrap_or(rest);
+    let (tag, message) = match rest.split_once(": ") {
+        Some((tag, message)) => (tag, message),
+        None => (rest.strip_suffix(':').unwrap_or(rest), ""),
+    };
+
+    Some(LogEntry {
+        timestamp_ms,
+        pid: pid.parse().ok()?,
+        tid: tid.parse().ok()?,
+        uid,
+        level,
+        tag: tag.trim().to_string(),
+        message: message.to_string(),
+        buffer: None,
+    })
+}
+
+impl CompiledFilter {
+    fn new(spec: LogFilter) -> Result<Self, String> {
+        let regex = spec
+            .message_regex
+            .as_deref()
+            .filter(|r| !r.is_empty())
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid message regex: {}", e))?;
+        Ok(CompiledFilter { spec, regex })
+    }
+
+    fn filters_processes(&self) -> bool {
+        !self.spec.pids.is_empty() || !self.spec.packages.is_empty()
+    }
+
+    fn matches(&self, entry: &LogEntry, package_pids: &Mutex<HashSet<u32>>) -> bool {
+        let spec = &self.spec;
+        if spec.min_level.is_some_and(|min| entry.level < min)
+            || (!spec.include_tags.is_empty() && !spec.include_tags.contains(&entry.tag))
+            || spec.exclude_tags.contains(&entry.tag)
+        {
+            return false;
+        }
+        if self.filters_processes()
+            && !spec.pids.contains(&entry.pid)
+            && !package_pids
+                .lock()
+                .is_ok_and(|pids| pids.contains(&entry.pid))
+        {
+            return false;
+        }
+        self.regex
+            .as_ref()
+            .is_none_or(|regex| regex.is_match(&entry.message))
+    }
+}
+
+fn process_of_packages(process: &str, packages: &[String]) -> bool {
+    packages.iter().any(|package| {
+        process == package
+            || process
+                .strip_prefix(package.as_str())
+                .is_some_and(|rest| rest.starts_with(':'))
+    })
+}
+
+/// PIDs of the running processes of `packages`, from `ps`.
+fn resolve_package_pids(adb_path: &str, device: &str, packages: &[String]) -> HashSet<u32> {
+    let Ok(output) = Command::new(adb_path)
+        .args(["-s", device, "shell", "ps -A -o PID,NAME 2>/dev/null || ps"])
+        .output()
+    else {
+        return HashSet::new();
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    // Old `ps` prints USER first, so find the PID column from the header
+    let pid_column = lines
+        .next()
+        .and_then(|header| header.split_whitespace().position(|c| c == "PID"))
+        .unwrap_or(0);
+    lines
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let name = columns.last()?;
+            if !process_of_packages(name, packages) {
+                return None;
+            }
+            columns.get(pid_column)?.parse().ok()
+        })
+        .collect()
+}
+
+/// Reads `am_proc_start` / `am_proc_died` events:
+///
+/// ```text
+/// am_proc_start: [0,5678,10123,com.example.app,activity,{com.example.app/.Main}]
+/// am_proc_died: [0,5678,com.example.app,900,17]
+/// ```
+///
+/// Releases before Lollipop print no user id first. Returns whether the
+/// process started, its PID and name.
+fn parse_process_event(entry: &LogEntry) -> Option<(bool, u32, String)> {
+    let started = match entry.tag.as_str() {
+        "am_proc_start" => true,
+        "am_proc_died" => false,
+        _ => return None,
+    };
+    let fields: Vec<&str> = entry
+        .message
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .collect();
+    let is_number = |index: usize| fields.get(index).is_some_and(|f| f.parse::<u32>().is_ok());
+    // Index of the PID and the process name, with and without the user id
+    let (pid, name) = match (started, is_number(2), is_number(1)) {
+        (true, true, _) => (1, 3),
+        (true, false, _) => (0, 2),
+        (false, _, true) => (1, 2),
+        (false, _, false) => (0, 1),
+    };
+    Some((
+        started,
+        fields.get(pid)?.parse().ok()?,
+        fields.get(name)?.to_string(),
+    ))
+}
+
+/// Starts following process starts and deaths in the events buffer, then
+/// resolves the PIDs already running so no start falls in between. `None`
+/// when the session was stopped meanwhile.
+fn watch_package_processes(
+    config: &SessionConfig,
+    session: &LogcatSession,
+    packages: &[String],
+) -> Result<Option<ChildStdout>, String> {
+    let mut child = Command::new(&config.adb_path)
+        .args([
+            "-s",
+            &config.device,
+            "logcat",
+            "-b",
+            "events",
+            "-v",
+            "epoch",
+            "-T",
+            "1",
+            "am_proc_start:I",
+            "am_proc_died:I",
+            "*:S",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let stdout = child.stdout.take();
+    if !session.store_child(&session.watcher, child) {
+        return Ok(None);
+    }
+
+    let pids = resolve_package_pids(&config.adb_path, &config.device, packages);
+    if let Ok(mut current) = session.package_pids.lock() {
+        *current = pids;
+    }
+    Ok(stdout)
+}
+
+/// Keeps `session.package_pids` current until the watcher is killed.
+fn track_package_processes(events: ChildStdout, session: &LogcatSession, packages: &[String]) {
+    for line in BufReader::new(events).split(b'\n').map_while(Result::ok) {
+        let line = String::from_utf8_lossy(&line);
+        let Some(entry) = parse_log_line(line.trim_end_matches('\r')) else {
+            continue;
+        };
+        let Some((started, pid, process)) = parse_process_event(&entry) else {
+            continue;
+        };
+        if !process_of_packages(&process, packages) {
+            continue;
+        }
+        if let Ok(mut pids) = session.package_pids.lock() {
+            if started {
+                pids.insert(pid);
+            } else {
+                pids.remove(&pid);
+            }
+        }
+    }
+}
+
+fn emit_status(app: &AppHandle, session_id: u64, state: LogcatState, message: Option<String>) {
+    let _ = app.emit(
+        "logcat-status",
+        LogcatStatus {
+            session_id,
+            state,
+            message,
+        },
+    );
+}
+
+/// Where to pick up after a restart. `-T <time>` prints entries at that
+/// time again, so the ones already emitted for it are skipped.
+#[derive(Default)]
+struct ResumePoint {
+    timestamp_ms: u64,
+    seen_at_timestamp: usize,
+}
+
+impl ResumePoint {
+    fn skip(&mut self, entry: &LogEntry, skipped_at_timestamp: &mut usize) -> bool {
+        if entry.timestamp_ms < self.timestamp_ms {
+            return true;
+        }
+        if entry.timestamp_ms == self.timestamp_ms && *skipped_at_timestamp < self.seen_at_timestamp
+        {
+            *skipped_at_timestamp += 1;
+            return true;
+        }
+        false
+    }
+
+    fn record(&mut self, entry: &LogEntry) {
+        if entry.timestamp_ms == self.timestamp_ms {
+            self.seen_at_timestamp += 1;
+        } else {
+            self.timestamp_ms = entry.timestamp_ms;
+            self.seen_at_timestamp = 1;
+        }
+    }
+}
+
+struct SessionConfig {
+    app: AppHandle,
+    adb_path: String,
+    device: String,
+    session_id: u64,
+    options: LogcatOptions,
+}
+
+/// Runs one `logcat` child until it exits and returns whether it printed
+/// anything, plus its stderr.
+fn follow_logcat(
+    config: &SessionConfig,
+    session: &LogcatSession,
+    resume: &mut Option<ResumePoint>,
+    with_uid: bool,
+) -> (bool, String) {
+    let Ok(filter) = session.filter.lock().map(|filter| filter.clone()) else {
+        return (false, String::new());
+    };
+    let packages = &filter.spec.packages;
+    if packages.is_empty() {
+        return stream_logcat(config, session, resume, with_uid, &filter);
+    }
+
+    let events = match watch_package_processes(config, session, packages) {
+        Ok(Some(events)) => events,
+        Ok(None) => return (false, String::new()),
+        Err(e) => return (false, e),
+    };
+    thread::scope(|scope| {
+        scope.spawn(|| track_package_processes(events, session, packages));
+        let result = stream_logcat(config, session, resume, with_uid, &filter);
+        session.stop_watcher();
+        result
+    })
+}
+
+/// Runs one `logcat` process and emits the entries that pass `filter`.
+fn stream_logcat(
+    config: &SessionConfig,
+    session: &LogcatSession,
+    resume: &mut Option<ResumePoint>,
+    with_uid: bool,
+    filter: &CompiledFilter,
+) -> (bool, String) {
+    let mut args = vec![
+        "-s".to_string(),
+        config.device.clone(),
+        "logcat".to_string(),
+        "-v".to_string(),
+        "epoch".to_string(),
+    ];
+    let buffers = config.options.buffers.as_deref().unwrap_or_default();
+    args.extend(buffer_args(buffers).into_iter().map(String::from));
+    if with_uid {
+        args.extend(["-v".to_string(), "uid".to_string()]);
+    }
+    args.push("-T".to_string());
+    args.push(match resume {
+        Some(point) => format!(
+            "{}.{:03}",
+            point.timestamp_ms / 1000,
+            point.timestamp_ms % 1000
+        ),
+        None => config
+            .options
+            .tail
+            .unwrap_or(DEFAULT_TAIL)
+            .max(1)
+            .to_string(),
+    });
+    // Let logcat drop entries below the minimum level before they reach adb
+    if let Some(level) = filter.spec.min_level {
+        args.push(format!("*:{}", level.letter()));
+    }
+
+    let child = Command::new(&config.adb_path)
+        .args(&args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return (false, format!("Failed to execute adb: {}", e)),
+    };
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    if !session.set_child(child) {
+        return (false, String::new());
+    }
+    emit_status(&config.app, config.session_id, LogcatState::Running, None);
+
+    let (sender, receiver) = mpsc::channel();
+    let reader = thread::spawn(move || {
+        let Some(stdout) = stdout else {
+            return;
+        };
+        let mut buffer = None;
+        for line in BufReader::new(stdout).split(b'\n').map_while(Result::ok) {
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\r');
+            if let Some(marker) = parse_buffer_marker(line) {
+                buffer = Some(marker);
+            } else if let Some(mut entry) = parse_log_line(line) {
+                entry.buffer = buffer;
+                if sender.send(entry).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    let mut any_output = false;
+    let mut skipped_at_timestamp = 0;
+    let mut batch = Vec::new();
+    let mut last_flush = Instant::now();
+    let point = resume.get_or_insert_with(ResumePoint::default);
+    loop {
+        let received = receiver.recv_timeout(BATCH_INTERVAL);
+        let done = matches!(received, Err(RecvTimeoutError::Disconnected));
+        if let Ok(entry) = received {
+            any_output = true;
+            if !point.skip(&entry, &mut skipped_at_timestamp) {
+                point.record(&entry);
+                if filter.matches(&entry, &session.package_pids) {
+                    batch.push(entry);
+                }
+            }
+        }
+        if !batch.is_empty()
+            && (done || batch.len() >= MAX_BATCH || last_flush.elapsed() >= BATCH_INTERVAL)
+        {
+            let _ = config.app.emit(
+                "logcat-entries",
+                LogcatBatch {
+                    session_id: config.session_id,
+                    entries: std::mem::take(&mut batch),
+                },
+            );
+            last_flush = Instant::now();
+        }
+        if done {
+            break;
+        }
+    }
+    let _ = reader.join();
+    session.wait_child();
+
+    let mut error = String::new();
+    if let Some(mut stderr) = stderr {
+        let _ = stderr.read_to_string(&mut error);
+    }
+    (any_output, error)
+}
+
+fn run_session(config: SessionConfig, session: Arc<LogcatSession>) {
+    let mut resume: Option<ResumePoint> = None;
+    let mut with_uid = true;
+    let mut was_paused = false;
+    let mut failure = None;
+
+    while !session.stopped.load(Ordering::SeqCst) {
+        if session.paused.load(Ordering::SeqCst) {
+            if !was_paused {
+                emit_status(&config.app, config.session_id, LogcatState::Paused, None);
+                was_paused = true;
+            }
+            thread::sleep(BATCH_INTERVAL);
+            continue;
+        }
+        was_paused = false;
+
+        let started = Instant::now();
+        session.restart.store(false, Ordering::SeqCst);
+        let (any_output, error) = follow_logcat(&config, &session, &mut resume, with_uid);
+        if session.interrupted() {
+            continue;
+        }
+        // Android 6 and older reject `-v uid`
+        if with_uid && !any_output && error.contains("uid") {
+            with_uid = false;
+            continue;
+        }
+
+        // logcat ends when the device goes away. If it is still there,
+        // logcat itself failed (e.g. an unreadable buffer) and retrying
+        // would only fail again.
+        let error = Some(error.trim().to_string()).filter(|e| !e.is_empty());
+        match device_online(&config.adb_path, &config.device) {
+            Ok(false) => {}
+            Ok(true) => {
+                failure = Some(error.unwrap_or_else(|| "logcat exited".to_string()));
+                break;
+            }
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        }
+
+        // Wait for the device and resume
+        emit_status(
+            &config.app,
+            config.session_id,
+            LogcatState::Reconnecting,
+            error,
+        );
+        if started.elapsed() < Duration::from_secs(1) {
+            thread::sleep(Duration::from_secs(1));
+        }
+        let waiter = Command::new(&config.adb_path)
+            .args(["-s", &config.device, "wait-for-device"])
+            .spawn();
+        if let Ok(waiter) = waiter {
+            if session.set_child(waiter) {
+                session.wait_child();
+            }
+        }
+    }
+
+    if failure.is_some() {
+        if let Ok(mut sessions) = SESSIONS.lock() {
+            sessions.remove(&config.session_id);
+        }
+    }
+    emit_status(
+        &config.app,
+        config.session_id,
+        LogcatState::Stopped,
+        failure,
+    );
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Starts following logcat on `device`. Parsed entries arrive in batches as
+/// `logcat-entries` events and state changes as `logcat-status` events.
+/// When the device disconnects the session waits for it and continues from
+/// the last entry it emitted. Entries not matching `options.filter` are
+/// dropped before they are emitted. Returns the session id.
+#[tauri::command]
+pub async fn start_logcat(
+    app: AppHandle,
+    device: String,
+    options: Option<LogcatOptions>,
+) -> Result<u64, String> {
+    let options = options.unwrap_or_default();
+    let filter = CompiledFilter::new(options.filter.clone().unwrap_or_default())?;
+    let adb_path = resolve_adb_path(&app);
+    let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+    let session = Arc::new(LogcatSession {
+        stopped: AtomicBool::new(false),
+        paused: AtomicBool::new(false),
+        restart: AtomicBool::new(false),
+        filter: Mutex::new(Arc::new(filter)),
+        package_pids: Mutex::new(HashSet::new()),
+        child: Mutex::new(None),
+        watcher: Mutex::new(None),
+    });
+    SESSIONS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(session_id, session.clone());
+
+    let config = SessionConfig {
+        app,
+        adb_path,
+        device,
+        session_id,
+        options,
+    };
+    thread::spawn(move || run_session(config, session));
+    Ok(session_id)
+}
+
+/// Pauses or resumes a session. A paused session stops reading from the
+/// device; resuming picks up from the last emitted entry, so nothing logged
+/// in between is lost (as long as it is still in the device's ring buffer).
+#[tauri::command]
+pub async fn pause_logcat(session_id: u64, paused: bool) -> Result<(), String> {
+    let session = session(session_id)?;
+    session.paused.store(paused, Ordering::SeqCst);
+    if paused {
+        session.kill_child();
+    }
+    Ok(())
+}
+
+/// Replaces the filter of a running session; `None` removes it. Entries
+/// logged after the last emitted one are matched against the new filter.
+#[tauri::command]
+pub async fn set_logcat_filter(session_id: u64, filter: Option<LogFilter>) -> Result<(), String> {
+    let compiled = CompiledFilter::new(filter.unwrap_or_default())?;
+    let session = session(session_id)?;
+    *session.filter.lock().map_err(|e| e.to_string())? = Arc::new(compiled);
+    // Package watching and the logcat level spec depend on the filter
+    session.restart.store(true, Ordering::SeqCst);
+    session.kill_child();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_logcat(session_id: u64) -> Result<(), String> {
+    let session = SESSIONS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&session_id)
+        .ok_or_else(|| format!("No logcat session with id {}", session_id))?;
+    session.stopped.store(true, Ordering::SeqCst);
+    session.kill_child();
+    Ok(())
+}
+
+/// Size and usage of every log buffer. Buffers the device lacks are
+/// returned with an `error`.
 #[tauri::command]
-pub async fn get_adb_logs(app: AppHandle, device: String) -> Result<String, String> {
-    // -d dumps the log to the screen and exits
-    // performing this async ensures the main thread isn't blocked by the process wait
+pub async fn get_log_buffers(app: AppHandle, device: String) -> Result<Vec<LogBufferInfo>, String> {
     let adb_path = resolve_adb_path(&app);
+    // logcat fails as a whole on an unknown buffer, so ask one at a time
+    let script = ALL_BUFFERS
+        .iter()
+        .map(|b| format!("echo '== {0}'; logcat -g -b {0} 2>&1", b.as_str()))
+        .collect::<Vec<_>>()
+        .join("; ");
     let output = Command::new(&adb_path)
-        .args(&["-s", &device, "logcat", "-d", "-t", "500"]) // get last 500 lines
+        .args(["-s", &device, "shell", &script])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() && output.stdout.is_empty() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(parse_buffer_sizes(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Resizes `buffers` (logcat's defaults when empty) to `size_bytes`, which
+/// logd rounds and limits to 64 KiB - 256 MiB.
+#[tauri::command]
+pub async fn set_log_buffer_size(
+    app: AppHandle,
+    device: String,
+    buffers: Vec<LogBuffer>,
+    size_bytes: u64,
+) -> Result<(), String> {
+    if !(MIN_BUFFER_SIZE..=MAX_BUFFER_SIZE).contains(&size_bytes) {
+        return Err("Buffer size must be between 64 KiB and 256 MiB".to_string());
+    }
+    let adb_path = resolve_adb_path(&app);
+    let size = format!("{}K", size_bytes / 1024);
+    let mut args = buffer_args(&buffers);
+    args.extend(["-G", &size]);
+    run_logcat(&adb_path, &device, &args).map(|_| ())
+}
+
+/// Clears `buffers`, or logcat's default buffers when empty.
+#[tauri::command]
+pub async fn clear_log_buffers(
+    app: AppHandle,
+    device: String,
+    buffers: Vec<LogBuffer>,
+) -> Result<(), String> {
+    let adb_path = resolve_adb_path(&app);
+    let mut args = buffer_args(&buffers);
+    args.push("-c");
+    run_logcat(&adb_path, &device, &args).map(|_| ())
+}
+
+/// Reads the binary `events` buffer and decodes each entry into typed
+/// fields named after the device's `/system/etc/event-log-tags`. Returns
+/// the last `tail` entries (default 500), oldest first.
+#[tauri::command]
+pub async fn get_event_logs(
+    app: AppHandle,
+    device: String,
+    tail: Option<u32>,
+) -> Result<Vec<EventLogEntry>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let tags = Command::new(&adb_path)
+        .args(["-s", &device, "shell", "cat /system/etc/event-log-tags"])
         .output()
         .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let tags = parse_event_log_tags(&String::from_utf8_lossy(&tags.stdout));
 
+    // exec-out keeps the binary output intact
+    let tail = tail.unwrap_or(DEFAULT_TAIL).max(1).to_string();
+    let output = Command::new(&adb_path)
+        .args([
+            "-s", &device, "exec-out", "logcat", "-b", "events", "-B", "-d", "-t", &tail,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
     if !output.status.success() {
         return Err(String::from_utf8_lossy(&output.stderr).to_string());
     }
-
-    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    Ok(decode_event_log(&output.stdout, &tags))
 }
@@ -0,0 +1,662 @@
+use crate::commands::hprof::convert_android_hprof;
+use crate::commands::packages::user_args;
+use crate::commands::utils::{device_online, file_safe_name, resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// One row of the main `dumpsys meminfo` table (`Native Heap`, `.so mmap`, ...).
+/// Columns the device does not print are `None`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MeminfoRow {
+    pub name: String,
+    pub pss_kb: Option<u64>,
+    pub private_dirty_kb: Option<u64>,
+    pub private_clean_kb: Option<u64>,
+    pub swap_pss_kb: Option<u64>,
+    pub rss_kb: Option<u64>,
+    pub heap_size_kb: Option<u64>,
+    pub heap_alloc_kb: Option<u64>,
+    pub heap_free_kb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MemoryCategory {
+    /// From the "App Summary" block: private (dirty + clean) PSS.
+    pub pss_kb: Option<u64>,
+    /// "App Summary" RSS, Android 10+.
+    pub rss_kb: Option<u64>,
+    /// Sum of the private dirty column over the rows in this category.
+    pub private_dirty_kb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MemoryBreakdown {
+    pub java_heap: MemoryCategory,
+    pub native_heap: MemoryCategory,
+    pub code: MemoryCategory,
+    pub stack: MemoryCategory,
+    pub graphics: MemoryCategory,
+    pub other: MemoryCategory,
+    pub system: MemoryCategory,
+}
+
+/// The "Objects" block. Counts the device does not report are `None`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MeminfoObjects {
+    pub views: Option<u64>,
+    pub view_root_impl: Option<u64>,
+    pub app_contexts: Option<u64>,
+    pub activities: Option<u64>,
+    pub assets: Option<u64>,
+    pub asset_managers: Option<u64>,
+    pub local_binders: Option<u64>,
+    pub proxy_binders: Option<u64>,
+    pub death_recipients: Option<u64>,
+    pub parcel_memory_kb: Option<u64>,
+    pub parcel_count: Option<u64>,
+    pub webviews: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ProcessMeminfo {
+    pub pid: u32,
+    pub process_name: String,
+    pub total_pss_kb: Option<u64>,
+    pub total_rss_kb: Option<u64>,
+    pub total_swap_pss_kb: Option<u64>,
+    pub breakdown: MemoryBreakdown,
+    pub objects: MeminfoObjects,
+    pub rows: Vec<MeminfoRow>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppMeminfo {
+    pub package_id: String,
+    /// One entry per running process of the package (`:remote` etc.).
+    pub processes: Vec<ProcessMeminfo>,
+    pub total_pss_kb: u64,
+    pub total_rss_kb: u64,
+}
+
+/// Payload of the `meminfo-sample` event.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MeminfoSample {
+    pub sampler_id: u64,
+    pub device: String,
+    pub package_id: String,
+    pub timestamp_ms: u64,
+    pub meminfo: Option<AppMeminfo>,
+    pub error: Option<String>,
+    /// The sampler gave up after this sample: the device went offline or
+    /// too many samples in a row failed.
+    pub stopped: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HeapDump {
+    pub package_id: String,
+    /// Local file: standard HPROF for Java heaps, text for native heaps.
+    pub path: String,
+    pub native: bool,
+    pub size_bytes: u64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Stop flags of the running samplers, by sampler id.
+static SAMPLERS: Mutex<BTreeMap<u64, Arc<AtomicBool>>> = Mutex::new(BTreeMap::new());
+static NEXT_SAMPLER_ID: AtomicU64 = AtomicU64::new(1);
+
+const MIN_SAMPLE_INTERVAL_MS: u64 = 500;
+/// Failed samples in a row after which a sampler stops on its own.
+const MAX_FAILED_SAMPLES: u32 = 60;
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// `** MEMINFO in pid 1234 [com.example.app] **`
+fn parse_process_header(line: &str) -> Option<(u32, String)> {
+    let rest = line.trim().strip_prefix("** MEMINFO in pid ")?;
+    let (pid, rest) = rest.split_once(' ')?;
+    let name = rest.trim_start_matches('[').split(']').next()?;
+    Some((pid.parse().ok()?, name.to_string()))
+}
+
+/// Parses `Key: value` pairs, several of which may share a line:
+/// `Local Binders:       15        Proxy Binders:       30`
+fn parse_key_values(line: &str, values: &mut HashMap<String, u64>) {
+    let mut key = Vec::new();
+    let mut tokens = line.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        match token.strip_suffix(':') {
+            Some(last) => {
+                key.push(last);
+                if let Some(value) = tokens.peek().and_then(|v| v.parse::<u64>().ok()) {
+                    tokens.next();
+                    values.insert(key.join(" "), value);
+                }
+                key.clear();
+            }
+            None => key.push(token),
+        }
+    }
+}
+
+/// Column names come from the two header rows combined word by word:
+/// `Pss`/`Total` -> `Pss Total`, `Private`/`Dirty` -> `Private Dirty`.
+fn table_columns(first: &str, second: &str) -> Vec<String> {
+    first
+        .split_whitespace()
+        .zip(second.split_whitespace())
+        .map(|(a, b)| format!("{} {}", a, b))
+        .collect()
+}
+
+fn parse_table_row(line: &str, columns: &[String]) -> Option<MeminfoRow> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let first_number = tokens.iter().position(|t| t.parse::<u64>().is_ok())?;
+    if first_number == 0 {
+        return None;
+    }
+
+    let mut row = MeminfoRow {
+        name: tokens[..first_number].join(" "),
+        ..Default::default()
+    };
+    // Rows without heap figures simply stop early, so values fill from the left.
+    for (column, token) in columns.iter().zip(&tokens[first_number..]) {
+        let value = token.parse::<u64>().ok();
+        match column.as_str() {
+            "Pss Total" => row.pss_kb = value,
+            "Private Dirty" => row.private_dirty_kb = value,
+            "Private Clean" => row.private_clean_kb = value,
+            "SwapPss Dirty" | "Swapped Dirty" | "Swap Dirty" => row.swap_pss_kb = value,
+            "Rss Total" => row.rss_kb = value,
+            "Heap Size" => row.heap_size_kb = value,
+            "Heap Alloc" => row.heap_alloc_kb = value,
+            "Heap Free" => row.heap_free_kb = value,
+            _ => {}
+        }
+    }
+    Some(row)
+}
+
+/// Which summary category a table row belongs to, following the grouping the
+/// framework uses for the "App Summary" block.
+fn row_category(name: &str) -> Option<&'static str> {
+    match name {
+        "Dalvik Heap" | ".art mmap" => Some("java_heap"),
+        "Native Heap" => Some("native_heap"),
+        ".so mmap" | ".jar mmap" | ".apk mmap" | ".ttf mmap" | ".dex mmap" | ".oat mmap" => {
+            Some("code")
+        }
+        "Stack" => Some("stack"),
+        "Gfx dev" | "EGL mtrack" | "GL mtrack" => Some("graphics"),
+        _ => None,
+    }
+}
+
+fn fill_private_dirty(process: &mut ProcessMeminfo) {
+    let mut sums: HashMap<&str, u64> = HashMap::new();
+    let mut total = None;
+    for row in &process.rows {
+        let Some(dirty) = row.private_dirty_kb else {
+            continue;
+        };
+        if row.name == "TOTAL" {
+            total = Some(dirty);
+        } else if let Some(category) = row_category(&row.name) {
+            *sums.entry(category).or_default() += dirty;
+        }
+    }
+    if total.is_none() && sums.is_empty() {
+        return;
+    }
+
+    let breakdown = &mut process.breakdown;
+    breakdown.java_heap.private_dirty_kb = Some(sums.get("java_heap").copied().unwrap_or(0));
+    breakdown.native_heap.private_dirty_kb = Some(sums.get("native_heap").copied().unwrap_or(0));
+    breakdown.code.private_dirty_kb = Some(sums.get("code").copied().unwrap_or(0));
+    breakdown.stack.private_dirty_kb = Some(sums.get("stack").copied().unwrap_or(0));
+    breakdown.graphics.private_dirty_kb = Some(sums.get("graphics").copied().unwrap_or(0));
+    breakdown.other.private_dirty_kb = total.map(|total| total.saturating_sub(sums.values().sum()));
+}
+
+fn apply_summary_line(line: &str, breakdown: &mut MemoryBreakdown) {
+    let Some((key, values)) = line.trim().split_once(':') else {
+        return;
+    };
+    let category = match key.trim() {
+        "Java Heap" => &mut breakdown.java_heap,
+        "Native Heap" => &mut breakdown.native_heap,
+        "Code" => &mut breakdown.code,
+        "Stack" => &mut breakdown.stack,
+        "Graphics" => &mut breakdown.graphics,
+        "Private Other" => &mut breakdown.other,
+        "System" => &mut breakdown.system,
+        _ => return,
+    };
+    let mut numbers = values.split_whitespace().filter_map(|v| v.parse().ok());
+    category.pss_kb = numbers.next();
+    category.rss_kb = numbers.next();
+}
+
+fn apply_objects(values: &HashMap<String, u64>, objects: &mut MeminfoObjects) {
+    let get = |key: &str| values.get(key).copied();
+    objects.views = get("Views");
+    objects.view_root_impl = get("ViewRootImpl");
+    objects.app_contexts = get("AppContexts");
+    objects.activities = get("Activities");
+    objects.assets = get("Assets");
+    objects.asset_managers = get("AssetManagers");
+    objects.local_binders = get("Local Binders");
+    objects.proxy_binders = get("Proxy Binders");
+    objects.death_recipients = get("Death Recipients");
+    objects.parcel_memory_kb = get("Parcel memory");
+    objects.parcel_count = get("Parcel count");
+    objects.webviews = get("WebViews");
+}
+
+#[derive(PartialEq)]
+enum Section {
+    Table,
+    Summary,
+    Objects,
+    Other,
+}
+
+/// Parses `dumpsys meminfo <package>`, which prints one block per process:
+///
+/// ```text
+/// ** MEMINFO in pid 1234 [com.example.app] **
+///                    Pss  Private  Private  SwapPss      Rss     Heap     Heap     Heap
+///                  Total    Dirty    Clean    Dirty    Total     Size    Alloc     Free
+///                 ------   ------   ------   ------   ------   ------   ------   ------
+///   Native Heap    10468    10408        0        0    12000    20480    14462     6017
+///   ...
+///  App Summary
+///                        Pss(KB)                        Rss(KB)
+///            Java Heap:     2936                          13000
+///   ...
+///            TOTAL PSS:    23000            TOTAL RSS:    55000      TOTAL SWAP PSS:   0
+///  Objects
+///                Views:       25         ViewRootImpl:        1
+/// ```
+fn parse_meminfo(dump: &str) -> Vec<ProcessMeminfo> {
+    let mut processes: Vec<ProcessMeminfo> = Vec::new();
+    let mut section = Section::Other;
+    let mut header: Option<&str> = None;
+    let mut columns: Vec<String> = Vec::new();
+    let mut objects = HashMap::new();
+
+    for line in dump.lines() {
+        if let Some((pid, process_name)) = parse_process_header(line) {
+            if let Some(process) = processes.last_mut() {
+                apply_objects(&objects, &mut process.objects);
+            }
+            objects.clear();
+            processes.push(ProcessMeminfo {
+                pid,
+                process_name,
+                ..Default::default()
+            });
+            section = Section::Table;
+            header = None;
+            columns.clear();
+            continue;
+        }
+        let Some(process) = processes.last_mut() else {
+            continue;
+        };
+
+        let trimmed = line.trim();
+        match trimmed {
+            "App Summary" => {
+                section = Section::Summary;
+                continue;
+            }
+            "Objects" => {
+                section = Section::Objects;
+                continue;
+            }
+            "SQL" | "DATABASES" | "Asset Allocations" | "Dalvik Details" => {
+                section = Section::Other;
+                continue;
+            }
+            _ => {}
+        }
+
+        match section {
+            Section::Table => {
+                if trimmed.is_empty() {
+                    if !process.rows.is_empty() {
+                        section = Section::Other;
+                    }
+                } else if trimmed.starts_with("---") {
+                    continue;
+                } else if columns.is_empty() {
+                    match header {
+                        None => header = Some(line),
+                        Some(first) => columns = table_columns(first, line),
+                    }
+                } else if let Some(row) = parse_table_row(line, &columns) {
+                    process.rows.push(row);
+                }
+            }
+            Section::Summary => {
+                if trimmed.starts_with("TOTAL") {
+                    let mut values = HashMap::new();
+                    parse_key_values(trimmed, &mut values);
+                    // Android 9 and older print `TOTAL:` instead of `TOTAL PSS:`
+                    process.total_pss_kb = values
+                        .get("TOTAL PSS")
+                        .or_else(|| values.get("TOTAL"))
+                        .copied();
+                    process.total_rss_kb = values.get("TOTAL RSS").copied();
+                    process.total_swap_pss_kb = values
+                        .get("TOTAL SWAP PSS")
+                        .or_else(|| values.get("TOTAL SWAP (KB)"))
+                        .copied();
+                } else {
+                    apply_summary_line(trimmed, &mut process.breakdown);
+                }
+            }
+            Section::Objects => parse_key_values(trimmed, &mut objects),
+            Section::Other => {}
+        }
+    }
+    if let Some(process) = processes.last_mut() {
+        apply_objects(&objects, &mut process.objects);
+    }
+
+    for process in &mut processes {
+        fill_private_dirty(process);
+        let total_row = process.rows.iter().find(|row| row.name == "TOTAL");
+        if process.total_pss_kb.is_none() {
+            process.total_pss_kb = total_row.and_then(|row| row.pss_kb);
+        }
+        if process.total_rss_kb.is_none() {
+            process.total_rss_kb = total_row.and_then(|row| row.rss_kb);
+        }
+    }
+    processes
+}
+
+fn fetch_app_meminfo(adb_path: &str, device: &str, package: &str) -> Result<AppMeminfo, String> {
+    let output = Command::new(adb_path)
+        .args([
+            "-s",
+            device,
+            "shell",
+            "dumpsys",
+            "meminfo",
+            &shell_quote(package),
+        ])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let dump = String::from_utf8_lossy(&output.stdout);
+    let processes = parse_meminfo(&dump);
+    if processes.is_empty() {
+        return Err(format!("{} is not running", package));
+    }
+
+    Ok(AppMeminfo {
+        package_id: package.to_string(),
+        total_pss_kb: processes.iter().filter_map(|p| p.total_pss_kb).sum(),
+        total_rss_kb: processes.iter().filter_map(|p| p.total_rss_kb).sum(),
+        processes,
+    })
+}
+
+/// How long to wait for the device to finish writing a heap dump.
+const HEAP_DUMP_TIMEOUT: Duration = Duration::from_secs(300);
+
+fn remote_size(adb_path: &str, device: &str, remote: &str) -> Option<u64> {
+    let output = Command::new(adb_path)
+        .args([
+            "-s",
+            device,
+            "shell",
+            &format!("stat -c %s {} 2>/dev/null", shell_quote(remote)),
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+/// Older releases return from `am dumpheap` before the file is written, so
+/// wait until it exists and its size has stopped changing.
+fn wait_for_dump(adb_path: &str, device: &str, remote: &str) -> Result<(), String> {
+    let started = std::time::Instant::now();
+    let mut last = None;
+    let mut stable_polls = 0;
+    while started.elapsed() < HEAP_DUMP_TIMEOUT {
+        thread::sleep(Duration::from_millis(500));
+        let size = remote_size(adb_path, device, remote).filter(|size| *size > 0);
+        if size.is_some() && size == last {
+            stable_polls += 1;
+            if stable_polls >= 3 {
+                return Ok(());
+            }
+        } else {
+            stable_polls = 0;
+        }
+        last = size;
+    }
+    Err("Timed out waiting for the heap dump to finish".to_string())
+}
+
+fn dump_and_pull(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    remote: &str,
+    local: &Path,
+    native: bool,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let mut args = vec![
+        "-s".to_string(),
+        device.to_string(),
+        "shell".to_string(),
+        "am".to_string(),
+        "dumpheap".to_string(),
+    ];
+    args.extend(user_args(user_id));
+    if native {
+        args.push("-n".to_string());
+    }
+    args.extend([shell_quote(package), shell_quote(remote)]);
+
+    let output = Command::new(adb_path)
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    if !output.status.success() || text.contains("Error") || text.contains("Exception") {
+        return Err(text.trim().to_string());
+    }
+    wait_for_dump(adb_path, device, remote)?;
+
+    let output = Command::new(adb_path)
+        .args(["-s", device, "pull", remote])
+        .arg(local)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_app_meminfo(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<AppMeminfo, String> {
+    let adb_path = resolve_adb_path(&app);
+    fetch_app_meminfo(&adb_path, &device, &package)
+}
+
+/// Samples `dumpsys meminfo` every `interval_ms` and emits each result as a
+/// `meminfo-sample` event. Samples taken while the app is not running carry
+/// an error instead of a reading, so gaps show up in the series. Sampling
+/// stops by itself once the device goes offline or `MAX_FAILED_SAMPLES`
+/// samples in a row fail. Returns the sampler id for `stop_meminfo_sampling`.
+#[tauri::command]
+pub async fn start_meminfo_sampling(
+    app: AppHandle,
+    device: String,
+    package: String,
+    interval_ms: u64,
+) -> Result<u64, String> {
+    let adb_path = resolve_adb_path(&app);
+    let interval = Duration::from_millis(interval_ms.max(MIN_SAMPLE_INTERVAL_MS));
+    let sampler_id = NEXT_SAMPLER_ID.fetch_add(1, Ordering::SeqCst);
+    let stop = Arc::new(AtomicBool::new(false));
+    SAMPLERS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .insert(sampler_id, stop.clone());
+
+    thread::spawn(move || {
+        let mut failed = 0;
+        while !stop.load(Ordering::SeqCst) {
+            let started = std::time::Instant::now();
+            let result = fetch_app_meminfo(&adb_path, &device, &package);
+            failed = if result.is_ok() { 0 } else { failed + 1 };
+            let give_up = result.is_err()
+                && (failed >= MAX_FAILED_SAMPLES
+                    || !device_online(&adb_path, &device).unwrap_or(false));
+            let sample = MeminfoSample {
+                sampler_id,
+                device: device.clone(),
+                package_id: package.clone(),
+                timestamp_ms: now_ms(),
+                error: result.as_ref().err().cloned(),
+                meminfo: result.ok(),
+                stopped: give_up,
+            };
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            let _ = app.emit("meminfo-sample", sample);
+            if give_up {
+                if let Ok(mut samplers) = SAMPLERS.lock() {
+                    samplers.remove(&sampler_id);
+                }
+                break;
+            }
+
+            // Sleep in short steps so stopping takes effect quickly
+            while started.elapsed() < interval && !stop.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(100));
+            }
+        }
+    });
+
+    Ok(sampler_id)
+}
+
+#[tauri::command]
+pub async fn stop_meminfo_sampling(sampler_id: u64) -> Result<(), String> {
+    let stop = SAMPLERS
+        .lock()
+        .map_err(|e| e.to_string())?
+        .remove(&sampler_id)
+        .ok_or_else(|| format!("No meminfo sampler with id {}", sampler_id))?;
+    stop.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Dumps the Java heap (or, with `native`, the native heap) of a running app
+/// with `am dumpheap`, pulls it into `dest` and removes it from the device.
+/// Java heaps are converted to standard HPROF so MAT, VisualVM and other
+/// desktop tools can open them.
+#[tauri::command]
+pub async fn capture_heap_dump(
+    app: AppHandle,
+    device: String,
+    package: String,
+    dest: String,
+    native: bool,
+    user_id: Option<u32>,
+) -> Result<HeapDump, String> {
+    let adb_path = resolve_adb_path(&app);
+    let base_name = format!("{}-{}", file_safe_name(&package), now_ms());
+    let extension = if native { "native.txt" } else { "hprof" };
+    let remote = format!("/data/local/tmp/{}.{}", base_name, extension);
+
+    let dest = Path::new(&dest);
+    fs::create_dir_all(dest).map_err(|e| format!("Failed to create folder: {}", e))?;
+    let local = dest.join(format!("{}.{}", base_name, extension));
+    let pulled = if native {
+        local.clone()
+    } else {
+        std::env::temp_dir().join(format!("green-bot-{}.android.hprof", base_name))
+    };
+
+    let result = dump_and_pull(
+        &adb_path, &device, &package, &remote, &pulled, native, user_id,
+    )
+    .and_then(|_| {
+        if native {
+            Ok(())
+        } else {
+            convert_android_hprof(&pulled, &local)
+        }
+    });
+
+    let _ = Command::new(&adb_path)
+        .args(["-s", &device, "shell", "rm", "-f", &remote])
+        .output();
+    if !native {
+        let _ = fs::remove_file(&pulled);
+    }
+    result?;
+
+    let size_bytes = fs::metadata(&local).map(|m| m.len()).unwrap_or(0);
+    Ok(HeapDump {
+        package_id: package,
+        path: local.to_string_lossy().to_string(),
+        native,
+        size_bytes,
+    })
+}
+
+/// Converts an Android heap dump that is already on disk to standard HPROF.
+#[tauri::command]
+pub async fn convert_hprof(input: String, output: String) -> Result<(), String> {
+    convert_android_hprof(Path::new(&input), Path::new(&output))
+}
@@ -1,8 +1,26 @@
 pub mod adb;
+pub mod apk;
+pub mod apk_info;
+pub mod apk_signing;
+pub mod appops;
+pub mod axml;
+pub mod background;
+pub mod crashes;
+pub mod debloat;
+pub mod deeplinks;
 pub mod diagnostics;
+pub mod event_log;
 pub mod files;
+pub mod hprof;
+pub mod intents;
+pub mod labels;
 pub mod logs;
+pub mod memory;
 pub mod mirror;
 pub mod packages;
+pub mod processes;
+pub mod snapshots;
+pub mod storage;
 pub mod terminal;
+pub mod tombstone;
 pub mod utils;
@@ -1,8 +1,15 @@
-use crate::commands::utils::resolve_adb_path;
+use crate::commands::apk::{install_session, InstallError, InstallOptions};
+use crate::commands::background::{fetch_background_state, BackgroundState};
+use crate::commands::storage::{clear_cache, fetch_package_storage, human_size};
+use crate::commands::utils::{resolve_adb_path, shell_quote};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
+use std::path::PathBuf;
 use std::process::Command;
-use tauri::AppHandle;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use tauri::{AppHandle, Emitter};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AppPackage {
@@ -10,6 +17,11 @@ pub struct AppPackage {
     pub path: String,
     pub is_system: bool,
     pub is_enabled: bool,
+    pub version_code: Option<u64>,
+    pub uid: Option<u32>,
+    pub installer: Option<String>,
+    /// Uninstalled for the user with `pm uninstall -k`; data is still on disk.
+    pub is_uninstalled_data_kept: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -24,9 +36,96 @@ pub struct PackageDetails {
     pub installer: String,
     pub min_sdk: String,
     pub target_sdk: String,
+    /// App, data and cache size; `Unknown` unless storage was requested.
     pub size: String,
     pub permissions: Vec<String>,
     pub is_enabled: bool,
+    /// Standby bucket, battery optimisation and background restrictions.
+    /// Only filled in by `get_package_details` with `include_background`.
+    pub background: Option<BackgroundState>,
+}
+
+/// A user on the device, as reported by `pm list users`. Work profiles show
+/// up here as managed profiles of their parent user.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AndroidUser {
+    pub id: u32,
+    pub name: String,
+    pub flags: u32,
+    pub running: bool,
+    pub is_managed_profile: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageAction {
+    Uninstall,
+    Enable,
+    Disable,
+    ClearData,
+    ClearCache,
+    ForceStop,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchItemResult {
+    pub package_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatchProgress {
+    pub device: String,
+    pub action: PackageAction,
+    pub completed: usize,
+    pub total: usize,
+    pub item: BatchItemResult,
+}
+
+/// Separates the sections of the combined `list_packages` shell command.
+const LIST_SECTION_MARKER: &str = "--green-bot-section--";
+
+/// Package ids of a plain `pm list packages` section.
+fn package_id_set(section: &str) -> HashSet<&str> {
+    section
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .collect()
+}
+
+/// Parses one line of `pm list packages -f -U -i --show-versioncode -u`:
+/// `package:/data/app/~~a==/com.example-b==/base.apk=com.example versionCode:42 installer=com.android.vending uid:10123`
+///
+/// Code paths may contain `=`, so the path and package id are split at the
+/// last `=` of the first token.
+pub(crate) fn parse_package_line(line: &str) -> Option<AppPackage> {
+    let mut tokens = line.split_whitespace();
+    let (path, package_id) = tokens.next()?.strip_prefix("package:")?.rsplit_once('=')?;
+
+    let mut package = AppPackage {
+        package_id: package_id.to_string(),
+        path: path.to_string(),
+        is_system: false,
+        is_enabled: true,
+        version_code: None,
+        uid: None,
+        installer: None,
+        is_uninstalled_data_kept: false,
+    };
+    for token in tokens {
+        if let Some(code) = token.strip_prefix("versionCode:") {
+            package.version_code = code.parse().ok();
+        } else if let Some(uid) = token.strip_prefix("uid:") {
+            // Shared UIDs print as "uid:10123,10124"
+            package.uid = uid.split(',').next().and_then(|u| u.parse().ok());
+        } else if let Some(installer) = token.strip_prefix("installer=") {
+            if installer != "null" {
+                package.installer = Some(installer.to_string());
+            }
+        }
+    }
+    Some(package)
 }
 
 #[tauri::command]
@@ -34,80 +133,56 @@ pub async fn list_packages(
     app: AppHandle,
     device: String,
     include_system: bool,
+    user_id: Option<u32>,
 ) -> Result<Vec<AppPackage>, String> {
-    // We will run two commands if include_system is true, or just one if false (optimization)
-    // Actually, `pm list packages -f` lists all. We can filter.
-    // simpler:
-    // `pm list packages -f -3` (Third party)
-    // `pm list packages -f -s` (System)
-
+    // Everything comes from one adb round trip; the sections are:
+    // 1. all packages, including ones uninstalled with data kept (-u)
+    // 2. disabled packages (-u, so the sections cover the same packages)
+    // 3. system packages (-u, so system apps removed for the user stay system)
+    // 4. installed packages (without -u)
+    // `--show-versioncode` needs Android 9; older releases fall back to a
+    // listing without version codes.
     let adb_path = resolve_adb_path(&app);
-    let mut packages = Vec::new();
-
-    // 0. Get list of disabled packages
-    let mut disabled_packages = HashSet::new();
-    if let Ok(output_disabled) = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "pm", "list", "packages", "-d"])
-        .output()
-    {
-        let stdout_disabled = String::from_utf8_lossy(&output_disabled.stdout);
-        for line in stdout_disabled.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Some(clean_line) = line.trim().strip_prefix("package:") {
-                disabled_packages.insert(clean_line.to_string());
-            }
-        }
-    }
+    let user = user_args(user_id).join(" ");
+    let script = format!(
+        "pm list packages -f -U -i --show-versioncode -u {user} 2>/dev/null \
+         || pm list packages -f -i -u {user}; \
+         echo {marker}; pm list packages -d -u {user}; \
+         echo {marker}; pm list packages -s -u {user}; \
+         echo {marker}; pm list packages {user}",
+        user = user,
+        marker = LIST_SECTION_MARKER,
+    );
 
-    // 1. Get Third Party Packages
-    let output_3rd = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "pm", "list", "packages", "-f", "-3"])
+    let output = Command::new(&adb_path)
+        .args(["-s", &device, "shell", &script])
         .output()
         .map_err(|e| e.to_string())?;
-
-    let stdout_3rd = String::from_utf8_lossy(&output_3rd.stdout);
-    for line in stdout_3rd.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        // line format: package:/path/to/apk=com.package.name
-        if let Some(clean_line) = line.strip_prefix("package:") {
-            if let Some((path, pkg)) = clean_line.rsplit_once('=') {
-                packages.push(AppPackage {
-                    package_id: pkg.to_string(),
-                    path: path.to_string(),
-                    is_system: false,
-                    is_enabled: !disabled_packages.contains(pkg),
-                });
-            }
-        }
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
     }
 
-    if include_system {
-        let output_sys = Command::new(&adb_path)
-            .args(&["-s", &device, "shell", "pm", "list", "packages", "-f", "-s"])
-            .output()
-            .map_err(|e| e.to_string())?;
-
-        let stdout_sys = String::from_utf8_lossy(&output_sys.stdout);
-        for line in stdout_sys.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-            if let Some(clean_line) = line.strip_prefix("package:") {
-                if let Some((path, pkg)) = clean_line.rsplit_once('=') {
-                    packages.push(AppPackage {
-                        package_id: pkg.to_string(),
-                        path: path.to_string(),
-                        is_system: true,
-                        is_enabled: !disabled_packages.contains(pkg),
-                    });
-                }
-            }
-        }
-    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let sections: Vec<&str> = stdout.split(LIST_SECTION_MARKER).collect();
+    let [all, disabled, system, installed] = sections[..] else {
+        return Err(format!("Unexpected pm output: {}", stdout.trim()));
+    };
+    let disabled = package_id_set(disabled);
+    let system = package_id_set(system);
+    let installed = package_id_set(installed);
+
+    let mut packages: Vec<AppPackage> = all
+        .lines()
+        .filter_map(parse_package_line)
+        .map(|mut package| {
+            let id = package.package_id.as_str();
+            package.is_system = system.contains(id);
+            package.is_enabled = !disabled.contains(id);
+            package.is_uninstalled_data_kept = !installed.contains(id);
+            package
+        })
+        .filter(|package| include_system || !package.is_system)
+        .collect();
 
     // Sort by package name
     packages.sort_by(|a, b| a.package_id.cmp(&b.package_id));
@@ -115,15 +190,54 @@ pub async fn list_packages(
     Ok(packages)
 }
 
+/// Reads the package's `dumpsys package` entry. `include_storage` also
+/// measures its size and `include_background` reads its background state;
+/// each takes several more adb calls (see `get_package_storage` and
+/// `get_background_state`).
 #[tauri::command]
 pub async fn get_package_details(
     app: AppHandle,
     device: String,
     package: String,
+    user_id: Option<u32>,
+    include_storage: Option<bool>,
+    include_background: Option<bool>,
 ) -> Result<PackageDetails, String> {
     let adb_path = resolve_adb_path(&app);
-    let output = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "dumpsys", "package", &package])
+    let mut details = fetch_package_details(&adb_path, &device, &package, user_id)?;
+    if include_storage.unwrap_or(false) {
+        // App + data + cache from diskstats, falling back to the APK files
+        // alone when the package is not in the diskstats tables yet.
+        if let Ok(storage) = fetch_package_storage(&adb_path, &device, &package) {
+            if let Some(bytes) = storage.total_bytes.or(storage.app_bytes) {
+                details.size = human_size(bytes);
+            }
+        }
+    }
+    if include_background.unwrap_or(false) {
+        details.background = Some(fetch_background_state(&adb_path, &device, &package));
+    }
+    Ok(details)
+}
+
+/// Reads `dumpsys package` for a single package. Shared with the APK
+/// commands, which need the installed version alongside their own output.
+/// `is_enabled` is the state for `user_id` (user 0 by default).
+pub(crate) fn fetch_package_details(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    user_id: Option<u32>,
+) -> Result<PackageDetails, String> {
+    let output = Command::new(adb_path)
+        .args([
+            "-s",
+            device,
+            "shell",
+            "dumpsys",
+            "package",
+            &shell_quote(package),
+        ])
         .output()
         .map_err(|e| e.to_string())?;
 
@@ -145,6 +259,7 @@ pub async fn get_package_details(
     let mut permissions = Vec::new();
     let mut in_permissions = false;
     let mut is_enabled = true;
+    let user_block = format!("User {}:", user_id.unwrap_or(0));
 
     for line in stdout.lines() {
         let trimmed = line.trim();
@@ -162,7 +277,7 @@ pub async fn get_package_details(
             }
         }
 
-        if trimmed.starts_with("User 0:") {
+        if trimmed.starts_with(&user_block) {
             if let Some(idx) = trimmed.find("enabled=") {
                 // simple check for 2, 3, 4
                 let val_str = &trimmed[idx + "enabled=".len()..];
@@ -219,23 +334,8 @@ pub async fn get_package_details(
         }
     }
 
-    // Get Size
-    let mut size = "Unknown".to_string();
-    if !path.is_empty() {
-        if let Ok(size_output) = Command::new(&adb_path)
-            .args(&["-s", &device, "shell", "du", "-h", &path])
-            .output()
-        {
-            let s_out = String::from_utf8_lossy(&size_output.stdout);
-            // output format: "25M    /data/app/..."
-            if let Some(s) = s_out.split_whitespace().next() {
-                size = s.to_string();
-            }
-        }
-    }
-
     Ok(PackageDetails {
-        package_id: package,
+        package_id: package.to_string(),
         version_name,
         version_code,
         first_install_time,
@@ -245,30 +345,132 @@ pub async fn get_package_details(
         installer,
         min_sdk,
         target_sdk,
-        size,
+        size: "Unknown".to_string(),
         permissions,
         is_enabled,
+        background: None,
     })
 }
 
+/// `--user <id>` for `pm`/`am`, or nothing to keep their default (the
+/// current foreground user).
+pub(crate) fn user_args(user_id: Option<u32>) -> Vec<String> {
+    match user_id {
+        Some(user) => vec!["--user".to_string(), user.to_string()],
+        None => Vec::new(),
+    }
+}
+
+/// Parses `pm list users`:
+/// `UserInfo{0:Owner:c13} running` / `UserInfo{10:Work profile:1030} running`
+fn parse_users(output: &str) -> Vec<AndroidUser> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line.trim();
+            let (info, rest) = trimmed.strip_prefix("UserInfo{")?.split_once('}')?;
+            let (id, info) = info.split_once(':')?;
+            let (name, flags) = info.rsplit_once(':')?;
+            let flags = u32::from_str_radix(flags, 16).unwrap_or(0);
+            Some(AndroidUser {
+                id: id.parse().ok()?,
+                name: name.to_string(),
+                flags,
+                running: rest.contains("running"),
+                // UserInfo.FLAG_MANAGED_PROFILE
+                is_managed_profile: flags & 0x20 != 0,
+            })
+        })
+        .collect()
+}
+
+/// Runs one of the single-package actions. The individual commands and
+/// `batch_package_action` all go through here.
+pub(crate) fn run_package_action(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    action: PackageAction,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let command: &[&str] = match action {
+        PackageAction::Uninstall => &["pm", "uninstall"],
+        PackageAction::Enable => &["pm", "enable"],
+        PackageAction::Disable => &["pm", "disable-user"],
+        PackageAction::ClearData => &["pm", "clear"],
+        PackageAction::ClearCache => return clear_cache(adb_path, device, package, user_id),
+        PackageAction::ForceStop => &["am", "force-stop"],
+    };
+    // `disable-user` has always targeted user 0 explicitly.
+    let user_id = match action {
+        PackageAction::Disable => Some(user_id.unwrap_or(0)),
+        _ => user_id,
+    };
+
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell"])
+        .args(command)
+        .args(user_args(user_id))
+        .arg(shell_quote(package))
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    match action {
+        PackageAction::Uninstall => {
+            if !stdout.contains("Success") {
+                return Err(stdout.to_string());
+            }
+        }
+        PackageAction::ForceStop => {
+            if !output.status.success() {
+                return Err(stderr.to_string());
+            }
+        }
+        // "Success" is usually printed to stdout.
+        PackageAction::Enable
+        | PackageAction::Disable
+        | PackageAction::ClearData
+        | PackageAction::ClearCache => {
+            if !output.status.success() || stderr.contains("Error") || stderr.contains("Failure") {
+                return Err(stderr.to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
 #[tauri::command]
-pub async fn uninstall_package(
-    app: AppHandle,
-    device: String,
-    package: String,
-) -> Result<String, String> {
+pub async fn list_users(app: AppHandle, device: String) -> Result<Vec<AndroidUser>, String> {
     let adb_path = resolve_adb_path(&app);
     let output = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "pm", "uninstall", &package])
+        .args(["-s", &device, "shell", "pm", "list", "users"])
         .output()
         .map_err(|e| e.to_string())?;
 
-    let result = String::from_utf8_lossy(&output.stdout);
-    if result.contains("Success") {
-        Ok("Uninstalled successfully".to_string())
-    } else {
-        Err(result.to_string())
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
     }
+    Ok(parse_users(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[tauri::command]
+pub async fn uninstall_package(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<String, String> {
+    let adb_path = resolve_adb_path(&app);
+    run_package_action(
+        &adb_path,
+        &device,
+        &package,
+        PackageAction::Uninstall,
+        user_id,
+    )?;
+    Ok("Uninstalled successfully".to_string())
 }
 
 #[tauri::command]
@@ -276,34 +478,27 @@ pub async fn install_package(
     app: AppHandle,
     device: String,
     path: String,
-) -> Result<String, String> {
+    options: Option<InstallOptions>,
+) -> Result<String, InstallError> {
     let adb_path = resolve_adb_path(&app);
-    let output = Command::new(&adb_path)
-        .args(&["-s", &device, "install", "-r", &path])
-        .output()
-        .map_err(|e| e.to_string())?;
+    let options = options.unwrap_or_default();
+    let result = install_session(&app, &adb_path, &device, &[PathBuf::from(&path)], &options)?;
 
-    let result = String::from_utf8_lossy(&output.stdout);
-    if result.contains("Success") {
-        Ok("Installed successfully".to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    match result.failure {
+        None => Ok("Installed successfully".to_string()),
+        Some(failure) => Err(InstallError::Failure(failure)),
     }
 }
 
 #[tauri::command]
-pub async fn enable_package(app: AppHandle, device: String, package: String) -> Result<(), String> {
+pub async fn enable_package(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<(), String> {
     let adb_path = resolve_adb_path(&app);
-    let output = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "pm", "enable", &package])
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    if !output.status.success() || stderr.contains("Error") || stderr.contains("Failure") {
-        return Err(stderr.to_string());
-    }
-    Ok(())
+    run_package_action(&adb_path, &device, &package, PackageAction::Enable, user_id)
 }
 
 #[tauri::command]
@@ -311,27 +506,16 @@ pub async fn disable_package(
     app: AppHandle,
     device: String,
     package: String,
+    user_id: Option<u32>,
 ) -> Result<(), String> {
     let adb_path = resolve_adb_path(&app);
-    let output = Command::new(&adb_path)
-        .args(&[
-            "-s",
-            &device,
-            "shell",
-            "pm",
-            "disable-user",
-            "--user",
-            "0",
-            &package,
-        ])
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    if !output.status.success() || stderr.contains("Error") || stderr.contains("Failure") {
-        return Err(stderr.to_string());
-    }
-    Ok(())
+    run_package_action(
+        &adb_path,
+        &device,
+        &package,
+        PackageAction::Disable,
+        user_id,
+    )
 }
 
 #[tauri::command]
@@ -339,19 +523,16 @@ pub async fn clear_package_data(
     app: AppHandle,
     device: String,
     package: String,
+    user_id: Option<u32>,
 ) -> Result<(), String> {
     let adb_path = resolve_adb_path(&app);
-    let output = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "pm", "clear", &package])
-        .output()
-        .map_err(|e| e.to_string())?;
-
-    let stderr = String::from_utf8_lossy(&output.stderr);
-    if !output.status.success() || stderr.contains("Error") || stderr.contains("Failure") {
-        // "Success" is usually printed to stdout.
-        return Err(stderr.to_string());
-    }
-    Ok(())
+    run_package_action(
+        &adb_path,
+        &device,
+        &package,
+        PackageAction::ClearData,
+        user_id,
+    )
 }
 
 #[tauri::command]
@@ -359,22 +540,181 @@ pub async fn force_stop_package(
     app: AppHandle,
     device: String,
     package: String,
+    user_id: Option<u32>,
 ) -> Result<(), String> {
     let adb_path = resolve_adb_path(&app);
+    run_package_action(
+        &adb_path,
+        &device,
+        &package,
+        PackageAction::ForceStop,
+        user_id,
+    )
+}
+
+/// Runs `action` on every package with at most `concurrency` adb calls in
+/// flight. Each finished item is reported as a `package-batch-progress`
+/// event; failures are collected instead of stopping the batch.
+#[tauri::command]
+pub async fn batch_package_action(
+    app: AppHandle,
+    device: String,
+    packages: Vec<String>,
+    action: PackageAction,
+    concurrency: Option<usize>,
+    user_id: Option<u32>,
+) -> Result<Vec<BatchItemResult>, String> {
+    let adb_path = resolve_adb_path(&app);
+    let total = packages.len();
+    let workers = concurrency.unwrap_or(4).clamp(1, 16).min(total.max(1));
+
+    let next = AtomicUsize::new(0);
+    let completed = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<BatchItemResult>>> =
+        Mutex::new((0..total).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(package) = packages.get(index) else {
+                    break;
+                };
+
+                let outcome = run_package_action(&adb_path, &device, package, action, user_id);
+                let item = BatchItemResult {
+                    package_id: package.clone(),
+                    success: outcome.is_ok(),
+                    error: outcome.err().map(|e| e.trim().to_string()),
+                };
+
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                let _ = app.emit(
+                    "package-batch-progress",
+                    BatchProgress {
+                        device: device.clone(),
+                        action,
+                        completed: done,
+                        total,
+                        item: item.clone(),
+                    },
+                );
+                if let Ok(mut results) = results.lock() {
+                    results[index] = Some(item);
+                }
+            });
+        }
+    });
+
+    Ok(results
+        .into_inner()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .flatten()
+        .collect())
+}
+
+/// Makes an app that is already installed for some user available to
+/// `user_id` as well, e.g. to copy an app into the work profile.
+#[tauri::command]
+pub async fn install_existing(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: u32,
+) -> Result<String, String> {
+    let adb_path = resolve_adb_path(&app);
+    let user = user_id.to_string();
     let output = Command::new(&adb_path)
-        .args(&["-s", &device, "shell", "am", "force-stop", &package])
+        .args([
+            "-s",
+            &device,
+            "shell",
+            "pm",
+            "install-existing",
+            "--user",
+            &user,
+            &shell_quote(&package),
+        ])
         .output()
         .map_err(|e| e.to_string())?;
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    // "Package com.example.app installed for user: 10"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.contains("installed for user") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}{}", stdout, stderr).trim().to_string());
+    }
+    Ok(stdout.trim().to_string())
+}
+
+/// `monkey` cannot target another user, so with a `user_id` the launcher
+/// activity is resolved for that user and started with `am start --user`.
+fn launch_for_user(adb_path: &str, device: &str, package: &str, user: u32) -> Result<(), String> {
+    let user = user.to_string();
+    let output = Command::new(adb_path)
+        .args([
+            "-s",
+            device,
+            "shell",
+            "cmd",
+            "package",
+            "resolve-activity",
+            "--brief",
+            "--user",
+            &user,
+            "-a",
+            "android.intent.action.MAIN",
+            "-c",
+            "android.intent.category.LAUNCHER",
+            &shell_quote(package),
+        ])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    // The last line is the component, e.g. com.example.app/.MainActivity
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let component = stdout
+        .lines()
+        .map(|line| line.trim())
+        .rfind(|line| line.contains('/'))
+        .ok_or_else(|| format!("No launcher activity for {} in user {}", package, user))?
+        .to_string();
+
+    let output = Command::new(adb_path)
+        .args([
+            "-s",
+            device,
+            "shell",
+            "am",
+            "start",
+            "--user",
+            &user,
+            "-n",
+            &shell_quote(&component),
+        ])
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !output.status.success() || stdout.contains("Error") {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{}{}", stdout, stderr).trim().to_string());
     }
     Ok(())
 }
 
 #[tauri::command]
-pub async fn launch_package(app: AppHandle, device: String, package: String) -> Result<(), String> {
+pub async fn launch_package(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<(), String> {
     let adb_path = resolve_adb_path(&app);
+    if let Some(user) = user_id {
+        return launch_for_user(&adb_path, &device, &package, user);
+    }
     let output = Command::new(&adb_path)
         .args(&[
             "-s",
@@ -382,7 +722,7 @@ pub async fn launch_package(app: AppHandle, device: String, package: String) ->
             "shell",
             "monkey",
             "-p",
-            &package,
+            &shell_quote(&package),
             "-c",
             "android.intent.category.LAUNCHER",
             "1",
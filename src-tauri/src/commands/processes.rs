@@ -0,0 +1,276 @@
+use crate::commands::utils::{adb_shell, resolve_adb_path};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// Process importance as the activity manager ranks it in its LRU list.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessImportance {
+    Persistent,
+    Foreground,
+    Visible,
+    Perceptible,
+    Service,
+    Cached,
+    Unknown,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageProcess {
+    pub pid: u32,
+    pub name: String,
+    pub importance: ProcessImportance,
+    /// The raw adjustment label from `dumpsys activity` (`fg`, `svcb`, `cch+5`, ...).
+    pub adj_label: Option<String>,
+    pub pss_kb: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunningPackage {
+    pub package_id: String,
+    pub processes: Vec<PackageProcess>,
+    pub total_pss_kb: Option<u64>,
+    pub importance: ProcessImportance,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+#[derive(Default)]
+struct AppProcess {
+    packages: Vec<String>,
+    adj_label: Option<String>,
+}
+
+fn importance_from_label(label: &str) -> ProcessImportance {
+    let label = label.trim_end_matches(|c: char| c == '+' || c.is_ascii_digit());
+    match label {
+        "pers" | "sys" => ProcessImportance::Persistent,
+        "fg" | "fore" | "top" => ProcessImportance::Foreground,
+        "vis" => ProcessImportance::Visible,
+        "prcp" | "prcl" | "prcm" | "hvy" | "bkup" => ProcessImportance::Perceptible,
+        "svc" | "svcb" | "svcl" | "psvc" | "fgs" | "bfgs" => ProcessImportance::Service,
+        "cch" | "home" | "prev" | "lstact" | "cac" | "cacr" => ProcessImportance::Cached,
+        _ => ProcessImportance::Unknown,
+    }
+}
+
+/// `1234:com.example.app:remote/u0a123` -> (1234, "com.example.app:remote")
+fn parse_pid_name(token: &str) -> Option<(u32, String)> {
+    let (pid, rest) = token.split_once(':')?;
+    let pid = pid.parse().ok()?;
+    let name = rest.rsplit_once('/').map(|(name, _)| name).unwrap_or(rest);
+    Some((pid, name.to_string()))
+}
+
+/// Parses the `*APP*` blocks and the LRU list of `dumpsys activity processes`.
+fn parse_activity_processes(dump: &str) -> HashMap<u32, AppProcess> {
+    let mut processes: HashMap<u32, AppProcess> = HashMap::new();
+    let mut current: Option<u32> = None;
+
+    for line in dump.lines() {
+        let trimmed = line.trim();
+
+        // *APP* UID 10123 ProcessRecord{8f2a1b3 1234:com.example.app/u0a123}
+        if trimmed.starts_with("*APP*") || trimmed.starts_with("*PERS*") {
+            current = trimmed
+                .split("ProcessRecord{")
+                .nth(1)
+                .and_then(|rest| rest.split_whitespace().nth(1))
+                .map(|token| token.trim_end_matches('}'))
+                .and_then(parse_pid_name)
+                .map(|(pid, _)| pid);
+            if let Some(pid) = current {
+                processes.entry(pid).or_default();
+            }
+            continue;
+        }
+
+        // packageList={com.example.app, com.example.shared}
+        if let (Some(pid), Some(list)) = (current, trimmed.strip_prefix("packageList={")) {
+            let packages = list
+                .trim_end_matches('}')
+                .split(',')
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect();
+            processes.entry(pid).or_default().packages = packages;
+            continue;
+        }
+
+        // Proc # 3: fg     T/A/TOP  LCM  t: 0 1234:com.example.app/u0a123 (top-activity)
+        if trimmed.starts_with("Proc #") || trimmed.starts_with("PERS #") {
+            let Some((_, rest)) = trimmed.split_once(": ") else {
+                continue;
+            };
+            let label = rest.split_whitespace().next().map(|l| l.to_string());
+            if let Some((pid, _)) = rest.split_whitespace().find_map(parse_pid_name) {
+                processes.entry(pid).or_default().adj_label = label;
+            }
+        }
+    }
+
+    processes
+}
+
+/// Parses the `Total PSS by process:` section of `dumpsys meminfo`:
+/// `    123,456K: com.example.app (pid 1234 / activities)`
+pub(crate) fn parse_pss_by_process(dump: &str) -> HashMap<u32, u64> {
+    let mut pss = HashMap::new();
+    let mut in_section = false;
+
+    for line in dump.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Total PSS by process") {
+            in_section = true;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if trimmed.is_empty() {
+            break;
+        }
+
+        let Some((size, rest)) = trimmed.split_once("K:") else {
+            continue;
+        };
+        let kb = size.replace(',', "").trim().parse::<u64>().ok();
+        let pid = rest
+            .split("(pid ")
+            .nth(1)
+            .and_then(|p| p.split(|c: char| !c.is_ascii_digit()).next())
+            .and_then(|p| p.parse::<u32>().ok());
+        if let (Some(kb), Some(pid)) = (kb, pid) {
+            pss.insert(pid, kb);
+        }
+    }
+
+    pss
+}
+
+/// `ps -A -o PID,NAME` or, before Android 8, plain `ps` -> pid to process
+/// name.
+fn parse_ps(output: &str) -> HashMap<u32, String> {
+    let mut lines = output.lines();
+    // Old `ps` prints USER first, so find the PID column from the header
+    let pid_column = lines
+        .next()
+        .and_then(|header| header.split_whitespace().position(|c| c == "PID"))
+        .unwrap_or(0);
+    lines
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            let pid = columns.get(pid_column)?.parse().ok()?;
+            let name = columns.last()?.to_string();
+            Some((pid, name))
+        })
+        .collect()
+}
+
+fn importance_rank(importance: ProcessImportance) -> u8 {
+    match importance {
+        ProcessImportance::Persistent => 0,
+        ProcessImportance::Foreground => 1,
+        ProcessImportance::Visible => 2,
+        ProcessImportance::Perceptible => 3,
+        ProcessImportance::Service => 4,
+        ProcessImportance::Cached => 5,
+        ProcessImportance::Unknown => 6,
+    }
+}
+
+pub(crate) fn fetch_running_packages(
+    adb_path: &str,
+    device: &str,
+    include_memory: bool,
+) -> Result<Vec<RunningPackage>, String> {
+    let ps = parse_ps(&adb_shell(
+        adb_path,
+        device,
+        &["ps -A -o PID,NAME 2>/dev/null || ps"],
+    )?);
+    let app_processes = parse_activity_processes(&adb_shell(
+        adb_path,
+        device,
+        &["dumpsys", "activity", "processes"],
+    )?);
+    let pss = if include_memory {
+        parse_pss_by_process(&adb_shell(adb_path, device, &["dumpsys", "meminfo"])?)
+    } else {
+        HashMap::new()
+    };
+
+    let mut packages: BTreeMap<String, Vec<PackageProcess>> = BTreeMap::new();
+    for (pid, app) in app_processes {
+        // Only report processes that are still alive according to ps
+        let Some(name) = ps.get(&pid) else {
+            continue;
+        };
+        let importance = app
+            .adj_label
+            .as_deref()
+            .map(importance_from_label)
+            .unwrap_or(ProcessImportance::Unknown);
+        let owners = if app.packages.is_empty() {
+            vec![name.split(':').next().unwrap_or(name).to_string()]
+        } else {
+            app.packages
+        };
+
+        for package in owners {
+            packages.entry(package).or_default().push(PackageProcess {
+                pid,
+                name: name.clone(),
+                importance,
+                adj_label: app.adj_label.clone(),
+                pss_kb: pss.get(&pid).copied(),
+            });
+        }
+    }
+
+    Ok(packages
+        .into_iter()
+        .map(|(package_id, mut processes)| {
+            processes.sort_by_key(|p| p.pid);
+            let importance = processes
+                .iter()
+                .map(|p| p.importance)
+                .min_by_key(|i| importance_rank(*i))
+                .unwrap_or(ProcessImportance::Unknown);
+            let total_pss_kb = if include_memory {
+                Some(processes.iter().filter_map(|p| p.pss_kb).sum())
+            } else {
+                None
+            };
+            RunningPackage {
+                package_id,
+                processes,
+                total_pss_kb,
+                importance,
+            }
+        })
+        .collect())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Lists packages that currently have live processes. `dumpsys meminfo` is
+/// slow on busy devices, so PSS is only collected when `include_memory` is set.
+#[tauri::command]
+pub async fn list_running_packages(
+    app: AppHandle,
+    device: String,
+    include_memory: bool,
+) -> Result<Vec<RunningPackage>, String> {
+    let adb_path = resolve_adb_path(&app);
+    fetch_running_packages(&adb_path, &device, include_memory)
+}
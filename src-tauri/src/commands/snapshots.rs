@@ -0,0 +1,412 @@
+use crate::commands::adb::get_prop;
+use crate::commands::utils::{app_data_subdir, file_safe_name, resolve_adb_path};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PackageRecord {
+    pub package_id: String,
+    pub version_code: Option<u64>,
+    pub version_name: String,
+    pub is_system: bool,
+    pub is_enabled: bool,
+    pub installer: String,
+    pub first_install_time: String,
+    pub last_update_time: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageSnapshot {
+    pub id: String,
+    /// The adb serial the snapshot was taken from.
+    pub device: String,
+    /// `ro.serialno`, which stays the same over USB and wireless adb.
+    pub device_id: String,
+    pub user_id: u32,
+    pub label: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: u64,
+    pub packages: Vec<PackageRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotSummary {
+    pub id: String,
+    pub device: String,
+    pub device_id: String,
+    pub user_id: u32,
+    pub label: Option<String>,
+    pub created_at: u64,
+    pub package_count: usize,
+}
+
+/// One side of a diff: a stored snapshot, or the current state of a device.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SnapshotSource {
+    Snapshot {
+        id: String,
+    },
+    Device {
+        device: String,
+        user_id: Option<u32>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageChangeKind {
+    Installed,
+    Removed,
+    Upgraded,
+    Downgraded,
+    /// Same version code, but reinstalled or updated in place.
+    Reinstalled,
+    Enabled,
+    Disabled,
+    InstallerChanged,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageChange {
+    pub package_id: String,
+    pub changes: Vec<PackageChangeKind>,
+    pub before: Option<PackageRecord>,
+    pub after: Option<PackageRecord>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SnapshotDiff {
+    pub from: SnapshotSummary,
+    pub to: SnapshotSummary,
+    pub changes: Vec<PackageChange>,
+    pub unchanged: usize,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+fn value_after<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(key)? + key.len();
+    line[start..].split_whitespace().next()
+}
+
+/// Parses the `Packages:` section of `dumpsys package packages`. Packages that
+/// are not installed for `user_id` (uninstalled with `-k`, or installed for
+/// another user only) are left out.
+fn parse_package_dump(dump: &str, user_id: u32) -> Vec<PackageRecord> {
+    let user_prefix = format!("User {}:", user_id);
+    let mut records = Vec::new();
+    // (record, installed for user_id, saw any "User N:" line)
+    let mut current: Option<(PackageRecord, Option<bool>, bool)> = None;
+    let mut in_packages = false;
+
+    // Old single-user releases print no "User N:" lines at all.
+    let finish = |current: Option<(PackageRecord, Option<bool>, bool)>,
+                  records: &mut Vec<PackageRecord>| {
+        if let Some((record, installed, any_user)) = current {
+            if installed.unwrap_or(!any_user) {
+                records.push(record);
+            }
+        }
+    };
+
+    for line in dump.lines() {
+        if !line.starts_with(' ') && !line.is_empty() {
+            // Stop before "Hidden system packages:", which repeats the
+            // original system versions of updated packages.
+            in_packages = line.starts_with("Packages:");
+            finish(current.take(), &mut records);
+            continue;
+        }
+        if !in_packages {
+            continue;
+        }
+
+        let trimmed = line.trim();
+        // Package [com.example.app] (8c1a2f3):
+        if let Some(rest) = trimmed.strip_prefix("Package [") {
+            finish(current.take(), &mut records);
+            let Some((package_id, _)) = rest.split_once(']') else {
+                continue;
+            };
+            current = Some((
+                PackageRecord {
+                    package_id: package_id.to_string(),
+                    version_code: None,
+                    version_name: String::new(),
+                    is_system: false,
+                    is_enabled: true,
+                    installer: String::new(),
+                    first_install_time: String::new(),
+                    last_update_time: String::new(),
+                },
+                None,
+                false,
+            ));
+            continue;
+        }
+
+        let Some((record, installed, any_user)) = current.as_mut() else {
+            continue;
+        };
+        if trimmed.starts_with("versionCode=") {
+            record.version_code = value_after(trimmed, "versionCode=").and_then(|v| v.parse().ok());
+        } else if let Some(name) = trimmed.strip_prefix("versionName=") {
+            record.version_name = name.to_string();
+        } else if trimmed.starts_with("flags=[") || trimmed.starts_with("pkgFlags=[") {
+            if trimmed.contains(" SYSTEM ") {
+                record.is_system = true;
+            }
+        } else if let Some(time) = trimmed.strip_prefix("firstInstallTime=") {
+            record.first_install_time = time.to_string();
+        } else if let Some(time) = trimmed.strip_prefix("lastUpdateTime=") {
+            record.last_update_time = time.to_string();
+        } else if let Some(installer) = trimmed.strip_prefix("installerPackageName=") {
+            record.installer = installer.to_string();
+        } else if trimmed.starts_with(&user_prefix) {
+            *any_user = true;
+            *installed = Some(value_after(trimmed, "installed=") != Some("false"));
+            // 0 = default, 1 = enabled, 2/3/4 = disabled variants
+            record.is_enabled = !matches!(value_after(trimmed, "enabled="), Some("2" | "3" | "4"));
+        } else if trimmed.starts_with("User ") && trimmed.contains("installed=") {
+            *any_user = true;
+        }
+    }
+    finish(current.take(), &mut records);
+
+    records.sort_by(|a, b| a.package_id.cmp(&b.package_id));
+    records
+}
+
+fn capture_snapshot(
+    adb_path: &str,
+    device: &str,
+    user_id: u32,
+    label: Option<String>,
+) -> Result<PackageSnapshot, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell", "dumpsys", "package", "packages"])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+
+    let packages = parse_package_dump(&String::from_utf8_lossy(&output.stdout), user_id);
+    if packages.is_empty() {
+        return Err("No packages found in dumpsys output".to_string());
+    }
+
+    let created_at = now_millis();
+    let device_id = get_prop(adb_path, device, "ro.serialno").unwrap_or_else(|| device.to_string());
+    Ok(PackageSnapshot {
+        id: format!("{}-{}", file_safe_name(&device_id), created_at),
+        device: device.to_string(),
+        device_id,
+        user_id,
+        label,
+        created_at,
+        packages,
+    })
+}
+
+fn summary(snapshot: &PackageSnapshot) -> SnapshotSummary {
+    SnapshotSummary {
+        id: snapshot.id.clone(),
+        device: snapshot.device.clone(),
+        device_id: snapshot.device_id.clone(),
+        user_id: snapshot.user_id,
+        label: snapshot.label.clone(),
+        created_at: snapshot.created_at,
+        package_count: snapshot.packages.len(),
+    }
+}
+
+fn snapshot_path(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    Ok(app_data_subdir(app, "snapshots")?.join(format!("{}.json", file_safe_name(id))))
+}
+
+fn load_snapshot(app: &AppHandle, id: &str) -> Result<PackageSnapshot, String> {
+    let bytes =
+        fs::read(snapshot_path(app, id)?).map_err(|_| format!("Snapshot {} not found", id))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("Corrupt snapshot: {}", e))
+}
+
+fn resolve_source(app: &AppHandle, source: SnapshotSource) -> Result<PackageSnapshot, String> {
+    match source {
+        SnapshotSource::Snapshot { id } => load_snapshot(app, &id),
+        SnapshotSource::Device { device, user_id } => {
+            let adb_path = resolve_adb_path(app);
+            capture_snapshot(&adb_path, &device, user_id.unwrap_or(0), None)
+        }
+    }
+}
+
+fn diff_records(
+    before: Option<&PackageRecord>,
+    after: Option<&PackageRecord>,
+) -> Vec<PackageChangeKind> {
+    let (before, after) = match (before, after) {
+        (None, Some(_)) => return vec![PackageChangeKind::Installed],
+        (Some(_), None) => return vec![PackageChangeKind::Removed],
+        (Some(before), Some(after)) => (before, after),
+        (None, None) => return Vec::new(),
+    };
+
+    let mut changes = Vec::new();
+    match (before.version_code, after.version_code) {
+        (Some(old), Some(new)) if new > old => changes.push(PackageChangeKind::Upgraded),
+        (Some(old), Some(new)) if new < old => changes.push(PackageChangeKind::Downgraded),
+        _ if before.last_update_time != after.last_update_time => {
+            changes.push(PackageChangeKind::Reinstalled)
+        }
+        _ => {}
+    }
+    if before.is_enabled != after.is_enabled {
+        changes.push(if after.is_enabled {
+            PackageChangeKind::Enabled
+        } else {
+            PackageChangeKind::Disabled
+        });
+    }
+    if before.installer != after.installer {
+        changes.push(PackageChangeKind::InstallerChanged);
+    }
+    changes
+}
+
+fn diff_snapshots_internal(
+    from: &PackageSnapshot,
+    to: &PackageSnapshot,
+    compare_times: bool,
+) -> SnapshotDiff {
+    let before: BTreeMap<&str, &PackageRecord> = from
+        .packages
+        .iter()
+        .map(|p| (p.package_id.as_str(), p))
+        .collect();
+    let after: BTreeMap<&str, &PackageRecord> = to
+        .packages
+        .iter()
+        .map(|p| (p.package_id.as_str(), p))
+        .collect();
+
+    let mut ids: Vec<&str> = before.keys().chain(after.keys()).copied().collect();
+    ids.sort();
+    ids.dedup();
+
+    let mut changes = Vec::new();
+    let mut unchanged = 0;
+    for id in ids {
+        let old = before.get(id).copied();
+        let new = after.get(id).copied();
+        let mut kinds = diff_records(old, new);
+        if !compare_times {
+            kinds.retain(|k| *k != PackageChangeKind::Reinstalled);
+        }
+        if kinds.is_empty() {
+            unchanged += 1;
+            continue;
+        }
+        changes.push(PackageChange {
+            package_id: id.to_string(),
+            changes: kinds,
+            before: old.cloned(),
+            after: new.cloned(),
+        });
+    }
+
+    SnapshotDiff {
+        from: summary(from),
+        to: summary(to),
+        changes,
+        unchanged,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Captures every package installed for the user, with version, enabled
+/// state, installer and install/update times, and stores it locally.
+#[tauri::command]
+pub async fn snapshot_packages(
+    app: AppHandle,
+    device: String,
+    label: Option<String>,
+    user_id: Option<u32>,
+) -> Result<SnapshotSummary, String> {
+    let adb_path = resolve_adb_path(&app);
+    let snapshot = capture_snapshot(&adb_path, &device, user_id.unwrap_or(0), label)?;
+
+    let bytes = serde_json::to_vec_pretty(&snapshot).map_err(|e| e.to_string())?;
+    fs::write(snapshot_path(&app, &snapshot.id)?, bytes)
+        .map_err(|e| format!("Failed to save snapshot: {}", e))?;
+    Ok(summary(&snapshot))
+}
+
+/// Lists stored snapshots, newest first. With a `device_id` only that
+/// device's snapshots are returned.
+#[tauri::command]
+pub async fn list_snapshots(
+    app: AppHandle,
+    device_id: Option<String>,
+) -> Result<Vec<SnapshotSummary>, String> {
+    let dir = app_data_subdir(&app, "snapshots")?;
+    let mut summaries: Vec<SnapshotSummary> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read(entry.path()).ok())
+        .filter_map(|bytes| serde_json::from_slice::<PackageSnapshot>(&bytes).ok())
+        .filter(|s| device_id.as_ref().is_none_or(|id| &s.device_id == id))
+        .map(|s| summary(&s))
+        .collect();
+    summaries.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    Ok(summaries)
+}
+
+#[tauri::command]
+pub async fn get_snapshot(app: AppHandle, id: String) -> Result<PackageSnapshot, String> {
+    load_snapshot(&app, &id)
+}
+
+#[tauri::command]
+pub async fn delete_snapshot(app: AppHandle, id: String) -> Result<(), String> {
+    fs::remove_file(snapshot_path(&app, &id)?).map_err(|_| format!("Snapshot {} not found", id))
+}
+
+/// Diffs two package inventories. Either side may be a stored snapshot or a
+/// live device, so this also compares two devices against each other. In that
+/// case install times always differ, so same-version reinstalls are only
+/// reported when both sides come from the same device.
+#[tauri::command]
+pub async fn diff_package_snapshots(
+    app: AppHandle,
+    from: SnapshotSource,
+    to: SnapshotSource,
+) -> Result<SnapshotDiff, String> {
+    let from = resolve_source(&app, from)?;
+    let to = resolve_source(&app, to)?;
+    let same_device = from.device_id == to.device_id;
+    Ok(diff_snapshots_internal(&from, &to, same_device))
+}
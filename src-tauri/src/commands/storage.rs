@@ -0,0 +1,268 @@
+use crate::commands::apk::package_apk_paths;
+use crate::commands::utils::{adb_shell, resolve_adb_path, shell_quote};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+/// Where the numbers in a `PackageStorage` came from.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageSource {
+    /// The package tables of `dumpsys diskstats`, which the system refreshes
+    /// roughly once a day.
+    Diskstats,
+    /// Only what the shell user can measure itself: APK files and external data.
+    Shell,
+}
+
+/// Per-package storage, in bytes. Fields are `None` when the device does not
+/// expose them to the shell user.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PackageStorage {
+    pub package_id: String,
+    /// APK, splits, odex and extracted native libraries.
+    pub app_bytes: Option<u64>,
+    /// User data, excluding cache.
+    pub data_bytes: Option<u64>,
+    pub cache_bytes: Option<u64>,
+    /// `/sdcard/Android/data/<package>`. On emulated storage diskstats already
+    /// counts this in `data_bytes` and `cache_bytes`.
+    pub external_data_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub source: StorageSource,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DiskstatsEntry {
+    app: u64,
+    data: u64,
+    cache: u64,
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Parses the cached package tables at the end of `dumpsys diskstats`:
+///
+/// ```text
+/// Package Names: ["com.example.app","com.example.other"]
+/// App Sizes: [12345678,2345678]
+/// App Data Sizes: [3456789,456789]
+/// Cache Sizes: [98765,8765]
+/// ```
+///
+/// "App Data Sizes" includes the cache, so it is subtracted here.
+fn parse_diskstats(dump: &str) -> HashMap<String, DiskstatsEntry> {
+    let field = |name: &str| {
+        dump.lines()
+            .find_map(|line| line.trim().strip_prefix(name))
+            .map(|value| value.trim().to_string())
+    };
+    let numbers = |name: &str| -> Vec<u64> {
+        field(name)
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or_default()
+    };
+
+    let names: Vec<String> = field("Package Names:")
+        .and_then(|value| serde_json::from_str(&value).ok())
+        .unwrap_or_default();
+    let app = numbers("App Sizes:");
+    let data = numbers("App Data Sizes:");
+    let cache = numbers("Cache Sizes:");
+
+    // The four arrays are written together; if they disagree the cache file is
+    // half-written and none of it can be trusted.
+    if app.len() != names.len() || data.len() != names.len() || cache.len() != names.len() {
+        return HashMap::new();
+    }
+
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let entry = DiskstatsEntry {
+                app: app[i],
+                data: data[i].saturating_sub(cache[i]),
+                cache: cache[i],
+            };
+            (name, entry)
+        })
+        .collect()
+}
+
+/// `du -sk <dir>` in bytes, or `None` if the directory is missing or unreadable.
+fn du_bytes(adb_path: &str, device: &str, path: &str) -> Option<u64> {
+    let output = adb_shell(adb_path, device, &["du", "-sk", path]).ok()?;
+    output
+        .split_whitespace()
+        .next()
+        .and_then(|kb| kb.parse::<u64>().ok())
+        .map(|kb| kb * 1024)
+}
+
+/// Sums the APK files reported by `pm path`. APKs are world-readable, so this
+/// works without root even though `/data/app` itself cannot be listed.
+fn apk_bytes(adb_path: &str, device: &str, package: &str) -> Option<u64> {
+    let paths = package_apk_paths(adb_path, device, package).ok()?;
+    let mut args = vec!["stat", "-c", "%s"];
+    args.extend(paths.iter().map(|p| p.as_str()));
+    let output = adb_shell(adb_path, device, &args).ok()?;
+    let sizes: Vec<u64> = output
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .collect();
+    if sizes.is_empty() {
+        None
+    } else {
+        Some(sizes.iter().sum())
+    }
+}
+
+pub(crate) fn fetch_package_storage(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+) -> Result<PackageStorage, String> {
+    let diskstats = adb_shell(adb_path, device, &["dumpsys", "diskstats"])
+        .map(|dump| parse_diskstats(&dump))
+        .unwrap_or_default();
+    let external_data_bytes = du_bytes(
+        adb_path,
+        device,
+        &shell_quote(&format!("/sdcard/Android/data/{}", package)),
+    );
+
+    if let Some(entry) = diskstats.get(package) {
+        return Ok(PackageStorage {
+            package_id: package.to_string(),
+            app_bytes: Some(entry.app),
+            data_bytes: Some(entry.data),
+            cache_bytes: Some(entry.cache),
+            external_data_bytes,
+            total_bytes: Some(entry.app + entry.data + entry.cache),
+            source: StorageSource::Diskstats,
+        });
+    }
+
+    // Not in the diskstats tables yet (freshly installed, or the daily job
+    // has not run): report what the shell can measure directly.
+    let app_bytes = apk_bytes(adb_path, device, package);
+    if app_bytes.is_none() {
+        return Err(format!("Package {} not found", package));
+    }
+    Ok(PackageStorage {
+        package_id: package.to_string(),
+        app_bytes,
+        data_bytes: None,
+        cache_bytes: None,
+        external_data_bytes,
+        total_bytes: None,
+        source: StorageSource::Shell,
+    })
+}
+
+/// Formats bytes the way `du -h` does (`25M`, `1.2G`), which is what
+/// `PackageDetails::size` has always shown.
+pub(crate) fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 || value >= 10.0 {
+        format!("{:.0}{}", value, UNITS[unit])
+    } else {
+        format!("{:.1}{}", value, UNITS[unit])
+    }
+}
+
+/// Clears only the cache directories of a package. `pm clear --cache-only`
+/// exists from Android 14; older releases fall back to `run-as`, which only
+/// works for debuggable apps. The external cache is cleared in both cases.
+pub(crate) fn clear_cache(
+    adb_path: &str,
+    device: &str,
+    package: &str,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let user = user_id.unwrap_or(0).to_string();
+    // The glob must stay outside the quotes
+    let external_cache = format!(
+        "{}/*",
+        shell_quote(&format!(
+            "/storage/emulated/{}/Android/data/{}/cache",
+            user, package
+        ))
+    );
+    let _ = adb_shell(adb_path, device, &["rm", "-rf", &external_cache]);
+
+    let output = adb_shell(
+        adb_path,
+        device,
+        &[
+            "pm",
+            "clear",
+            "--cache-only",
+            "--user",
+            &user,
+            &shell_quote(package),
+        ],
+    );
+    if matches!(&output, Ok(stdout) if stdout.contains("Success")) {
+        return Ok(());
+    }
+
+    let output = adb_shell(
+        adb_path,
+        device,
+        &[
+            "run-as",
+            "--user",
+            &user,
+            &shell_quote(package),
+            "sh",
+            "-c",
+            "'rm -rf cache/* code_cache/*'",
+        ],
+    );
+    match output {
+        Ok(stdout) if !stdout.contains("run-as:") => Ok(()),
+        _ => Err(format!(
+            "Clearing only the cache of {} needs Android 14 or a debuggable build",
+            package
+        )),
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_package_storage(
+    app: AppHandle,
+    device: String,
+    package: String,
+) -> Result<PackageStorage, String> {
+    let adb_path = resolve_adb_path(&app);
+    fetch_package_storage(&adb_path, &device, &package)
+}
+
+#[tauri::command]
+pub async fn clear_package_cache(
+    app: AppHandle,
+    device: String,
+    package: String,
+    user_id: Option<u32>,
+) -> Result<(), String> {
+    let adb_path = resolve_adb_path(&app);
+    clear_cache(&adb_path, &device, &package, user_id)
+}
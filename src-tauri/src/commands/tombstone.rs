@@ -0,0 +1,663 @@
+//! Parser for native crash tombstones (`debuggerd` text format) and
+//! symbolization of their backtraces with unstripped libraries from a host
+//! folder, matched by GNU BuildId.
+
+use addr2line::Loader;
+use object::read::ReadCache;
+use object::Object;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// ============================================================================
+// Data Structures
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TombstoneSignal {
+    pub number: u32,
+    /// `SIGSEGV`
+    pub name: String,
+    pub code: Option<i32>,
+    /// `SEGV_MAPERR`
+    pub code_name: Option<String>,
+    /// `None` when the signal has no fault address (`--------`).
+    pub fault_addr: Option<u64>,
+}
+
+/// A location from the symbol files. Inlined calls produce several of these
+/// for one frame, innermost first.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceLocation {
+    pub function: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BacktraceFrame {
+    pub index: u32,
+    /// Address relative to the start of the ELF file, as printed.
+    pub pc: u64,
+    pub library: Option<String>,
+    /// Offset of the library inside an APK (`base.apk!libfoo.so (offset 0x..)`).
+    pub library_offset: Option<u64>,
+    /// Function as symbolized on the device, usually without line info.
+    pub function: Option<String>,
+    pub function_offset: Option<u64>,
+    pub build_id: Option<String>,
+    /// Filled in by `symbolize_tombstone` when a matching symbol file exists.
+    pub locations: Vec<SourceLocation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct MemoryMapEntry {
+    pub start: u64,
+    pub end: u64,
+    pub permissions: String,
+    pub offset: Option<u64>,
+    pub name: Option<String>,
+    pub build_id: Option<String>,
+    /// Marked with `--->`: the fault address lies in this mapping.
+    pub contains_fault: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TombstoneThread {
+    pub tid: u32,
+    pub name: Option<String>,
+    pub registers: Vec<(String, u64)>,
+    pub backtrace: Vec<BacktraceFrame>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Tombstone {
+    pub build_fingerprint: Option<String>,
+    pub abi: Option<String>,
+    pub timestamp: Option<String>,
+    pub pid: Option<u32>,
+    pub process: Option<String>,
+    pub uid: Option<u32>,
+    pub signal: Option<TombstoneSignal>,
+    pub cause: Option<String>,
+    pub abort_message: Option<String>,
+    /// The thread that crashed.
+    pub crashing_thread: TombstoneThread,
+    pub other_threads: Vec<TombstoneThread>,
+    pub memory_map: Vec<MemoryMapEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SymbolizedTombstone {
+    pub tombstone: Tombstone,
+    /// Frames that got source locations, out of all frames with a library.
+    pub symbolized_frames: usize,
+    pub total_frames: usize,
+    /// BuildIds seen in the backtrace with no matching file in the folder.
+    pub missing_build_ids: Vec<String>,
+    /// Plain text report of the crash with symbolized frames.
+    pub report: String,
+}
+
+// ============================================================================
+// Parsing
+// ============================================================================
+
+fn parse_hex(value: &str) -> Option<u64> {
+    let value = value.trim().trim_start_matches("0x").replace('\'', "");
+    u64::from_str_radix(&value, 16).ok()
+}
+
+fn quoted(value: &str) -> String {
+    value.trim().trim_matches('\'').to_string()
+}
+
+/// Splits `text (a) (b (c)) (d)` into `text` and its top-level parenthesised
+/// groups `a`, `b (c)`, `d`.
+fn split_paren_groups(text: &str) -> (String, Vec<String>) {
+    let mut head = String::new();
+    let mut groups = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+    for c in text.chars() {
+        match c {
+            '(' => {
+                if depth > 0 {
+                    current.push(c);
+                }
+                depth += 1;
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    groups.push(std::mem::take(&mut current));
+                } else {
+                    current.push(c);
+                }
+            }
+            _ if depth > 0 => current.push(c),
+            _ if groups.is_empty() => head.push(c),
+            _ => {}
+        }
+    }
+    (head.trim().to_string(), groups)
+}
+
+/// `pid: 1234, tid: 1250, name: RenderThread  >>> com.example.app <<<`
+fn parse_thread_line(line: &str) -> Option<(u32, u32, Option<String>, Option<String>)> {
+    let rest = line.strip_prefix("pid: ")?;
+    let (pid, rest) = rest.split_once(", tid: ")?;
+    let (tid, rest) = rest.split_once(", name: ").unwrap_or((rest, ""));
+    let (name, process) = match rest.split_once(">>>") {
+        Some((name, process)) => (name, Some(process.trim().trim_end_matches("<<<").trim())),
+        None => (rest, None),
+    };
+    let name = Some(name.trim()).filter(|n| !n.is_empty());
+    Some((
+        pid.trim().parse().ok()?,
+        tid.trim().parse().ok()?,
+        name.map(|n| n.to_string()),
+        process.map(|p| p.to_string()),
+    ))
+}
+
+/// `signal 11 (SIGSEGV), code 1 (SEGV_MAPERR), fault addr 0x0000000000000000`
+fn parse_signal_line(line: &str) -> Option<TombstoneSignal> {
+    let rest = line.strip_prefix("signal ")?;
+    let mut signal = TombstoneSignal::default();
+    for part in rest.split(", ") {
+        if let Some(addr) = part.strip_prefix("fault addr ") {
+            signal.fault_addr = parse_hex(addr);
+        } else if let Some(code) = part.strip_prefix("code ") {
+            let (number, groups) = split_paren_groups(code);
+            signal.code = number.parse().ok();
+            signal.code_name = groups.into_iter().next();
+        } else {
+            let (number, groups) = split_paren_groups(part);
+            signal.number = number.parse().ok()?;
+            signal.name = groups.into_iter().next().unwrap_or_default();
+        }
+    }
+    Some(signal)
+}
+
+/// `    x0  0000000000000000  x1  0000007fe0c4e1c8  x2  ...`
+fn parse_register_line(line: &str) -> Option<Vec<(String, u64)>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+        return None;
+    }
+    tokens
+        .chunks(2)
+        .map(|pair| {
+            let name = pair[0];
+            let valid_name = name.len() <= 5 && name.chars().all(|c| c.is_ascii_alphanumeric());
+            let valid_value = pair[1].len() >= 8 && pair[1].chars().all(|c| c.is_ascii_hexdigit());
+            if !valid_name || !valid_value {
+                return None;
+            }
+            Some((name.to_string(), parse_hex(pair[1])?))
+        })
+        .collect()
+}
+
+/// `#01 pc 000000000004c8c4  /data/app/.../libfoo.so (offset 0x1000) (foo()+20) (BuildId: ab12)`
+fn parse_frame_line(line: &str) -> Option<BacktraceFrame> {
+    let rest = line.trim().strip_prefix('#')?;
+    let (index, rest) = rest.split_once(char::is_whitespace)?;
+    let rest = rest.trim_start().strip_prefix("pc ")?.trim_start();
+    let (pc, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    let mut frame = BacktraceFrame {
+        index: index.parse().ok()?,
+        pc: parse_hex(pc)?,
+        ..Default::default()
+    };
+
+    let (library, groups) = split_paren_groups(rest);
+    let mut library = Some(library).filter(|l| !l.is_empty());
+    for group in groups {
+        if let Some(build_id) = group.strip_prefix("BuildId: ") {
+            frame.build_id = Some(build_id.trim().to_string());
+        } else if let Some(offset) = group.strip_prefix("offset ") {
+            frame.library_offset = parse_hex(offset);
+        } else if group == "deleted" {
+            library = library.map(|l| format!("{} (deleted)", l));
+        } else {
+            match group.rsplit_once('+') {
+                Some((function, offset)) if offset.chars().all(|c| c.is_ascii_digit()) => {
+                    frame.function = Some(function.to_string());
+                    frame.function_offset = offset.parse().ok();
+                }
+                _ => frame.function = Some(group),
+            }
+        }
+    }
+    frame.library = library;
+    Some(frame)
+}
+
+/// `    00000070'c7f4c000-00000070'c7f5ffff r-x  3a000  14000  /system/lib64/libc.so (BuildId: ab12)`
+fn parse_map_line(line: &str) -> Option<MemoryMapEntry> {
+    let (contains_fault, line) = match line.trim().strip_prefix("--->") {
+        Some(rest) => (true, rest),
+        None => (false, line.trim()),
+    };
+    let mut tokens = line.split_whitespace();
+    let (start, end) = tokens.next()?.split_once('-')?;
+    let permissions = tokens.next()?.to_string();
+    if permissions.len() != 3 {
+        return None;
+    }
+
+    let mut entry = MemoryMapEntry {
+        start: parse_hex(start)?,
+        end: parse_hex(end)?,
+        permissions,
+        contains_fault,
+        ..Default::default()
+    };
+    // Offset and size columns, then the name
+    let rest: Vec<&str> = tokens.collect();
+    let numbers = rest
+        .iter()
+        .take_while(|t| t.chars().all(|c| c.is_ascii_hexdigit()))
+        .count();
+    entry.offset = rest
+        .first()
+        .filter(|_| numbers > 0)
+        .and_then(|t| parse_hex(t));
+    let (name, groups) = split_paren_groups(&rest[numbers..].join(" "));
+    entry.name = Some(name).filter(|n| !n.is_empty());
+    entry.build_id = groups
+        .iter()
+        .find_map(|g| g.strip_prefix("BuildId: "))
+        .map(|b| b.trim().to_string());
+    Some(entry)
+}
+
+#[derive(PartialEq)]
+enum TombstoneSection {
+    Header,
+    Backtrace,
+    MemoryMap,
+    Other,
+}
+
+/// Parses a tombstone as written by `debuggerd` (and stored in DropBox as
+/// `SYSTEM_TOMBSTONE`). Threads other than the crashing one follow
+/// `--- --- ---` separators.
+pub fn parse_tombstone_text(text: &str) -> Tombstone {
+    let mut tombstone = Tombstone::default();
+    let mut section = TombstoneSection::Header;
+    let mut thread_index: Option<usize> = None;
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        if line.starts_with("--- --- ---") {
+            tombstone.other_threads.push(TombstoneThread::default());
+            thread_index = Some(tombstone.other_threads.len() - 1);
+            section = TombstoneSection::Header;
+            continue;
+        }
+        if line == "backtrace:" {
+            section = TombstoneSection::Backtrace;
+            continue;
+        }
+        if line.starts_with("memory map") {
+            section = TombstoneSection::MemoryMap;
+            continue;
+        }
+        if line.starts_with("memory near")
+            || line.starts_with("code around")
+            || line.starts_with("stack:")
+            || line.starts_with("open files:")
+            || line.starts_with("log ")
+        {
+            section = TombstoneSection::Other;
+            continue;
+        }
+
+        let thread = match thread_index {
+            Some(index) => &mut tombstone.other_threads[index],
+            None => &mut tombstone.crashing_thread,
+        };
+
+        match section {
+            TombstoneSection::Header => {
+                if let Some((pid, tid, name, process)) = parse_thread_line(line) {
+                    thread.tid = tid;
+                    thread.name = name;
+                    if thread_index.is_none() {
+                        tombstone.pid = Some(pid);
+                        tombstone.process = process.or(tombstone.process.take());
+                    }
+                } else if let Some(registers) = parse_register_line(line) {
+                    thread.registers.extend(registers);
+                } else if thread_index.is_some() {
+                    continue;
+                } else if let Some(signal) = parse_signal_line(line) {
+                    tombstone.signal = Some(signal);
+                } else if let Some(value) = line.strip_prefix("Build fingerprint: ") {
+                    tombstone.build_fingerprint = Some(quoted(value));
+                } else if let Some(value) = line.strip_prefix("ABI: ") {
+                    tombstone.abi = Some(quoted(value));
+                } else if let Some(value) = line.strip_prefix("Timestamp: ") {
+                    tombstone.timestamp = Some(value.trim().to_string());
+                } else if let Some(value) = line.strip_prefix("Cmdline: ") {
+                    tombstone.process.get_or_insert(value.trim().to_string());
+                } else if let Some(value) = line.strip_prefix("uid: ") {
+                    tombstone.uid = value.trim().parse().ok();
+                } else if let Some(value) = line.strip_prefix("Cause: ") {
+                    tombstone.cause = Some(value.trim().to_string());
+                } else if let Some(value) = line.strip_prefix("Abort message: ") {
+                    tombstone.abort_message = Some(quoted(value));
+                }
+            }
+            TombstoneSection::Backtrace => {
+                if let Some(frame) = parse_frame_line(line) {
+                    thread.backtrace.push(frame);
+                }
+            }
+            TombstoneSection::MemoryMap => {
+                if let Some(entry) = parse_map_line(raw) {
+                    tombstone.memory_map.push(entry);
+                }
+            }
+            TombstoneSection::Other => {}
+        }
+    }
+    tombstone
+}
+
+// ============================================================================
+// Symbolization
+// ============================================================================
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn is_elf(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut f| f.read_exact(&mut magic))
+        .is_ok()
+        && magic == *b"\x7fELF"
+}
+
+fn elf_build_id(path: &Path) -> Option<String> {
+    let cache = ReadCache::new(File::open(path).ok()?);
+    let file = object::File::parse(&cache).ok()?;
+    file.build_id().ok()?.map(hex_string)
+}
+
+/// Indexes every ELF file below `dir` by BuildId, and by file name for
+/// tombstones that predate BuildIds.
+fn index_symbol_files(dir: &Path) -> (HashMap<String, PathBuf>, HashMap<String, PathBuf>) {
+    let mut by_build_id = HashMap::new();
+    let mut by_name = HashMap::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !is_elf(&path) {
+                continue;
+            }
+            if let Some(build_id) = elf_build_id(&path) {
+                by_build_id.insert(build_id, path.clone());
+            }
+            if let Some(name) = path.file_name() {
+                by_name.insert(name.to_string_lossy().to_string(), path);
+            }
+        }
+    }
+    (by_build_id, by_name)
+}
+
+fn frame_locations(loader: &Loader, pc: u64) -> Vec<SourceLocation> {
+    let mut locations = Vec::new();
+    if let Ok(mut frames) = loader.find_frames(pc) {
+        while let Ok(Some(frame)) = frames.next() {
+            let location = frame.location.as_ref();
+            locations.push(SourceLocation {
+                function: frame
+                    .function
+                    .as_ref()
+                    .and_then(|f| f.demangle().ok())
+                    .map(|f| f.to_string()),
+                file: location.and_then(|l| l.file).map(|f| f.to_string()),
+                line: location.and_then(|l| l.line),
+                column: location.and_then(|l| l.column),
+            });
+        }
+    }
+    // No DWARF for this address: fall back to the symbol table
+    if locations.is_empty() {
+        if let Some(symbol) = loader.find_symbol(pc) {
+            locations.push(SourceLocation {
+                function: Some(addr2line::demangle_auto(symbol.into(), None).to_string()),
+                file: None,
+                line: None,
+                column: None,
+            });
+        }
+    }
+    locations
+}
+
+fn library_file_name(library: &str) -> &str {
+    library
+        .rsplit(['/', '!'])
+        .next()
+        .unwrap_or(library)
+        .trim_end_matches(" (deleted)")
+}
+
+fn format_frame(report: &mut String, frame: &BacktraceFrame) {
+    let library = frame.library.as_deref().unwrap_or("<unknown>");
+    let _ = write!(
+        report,
+        "  #{:02} pc {:016x}  {}",
+        frame.index, frame.pc, library
+    );
+    if frame.locations.is_empty() {
+        if let Some(function) = &frame.function {
+            let _ = write!(report, " ({}", function);
+            if let Some(offset) = frame.function_offset {
+                let _ = write!(report, "+{}", offset);
+            }
+            report.push(')');
+        }
+        report.push('\n');
+        return;
+    }
+    report.push('\n');
+    for (index, location) in frame.locations.iter().enumerate() {
+        let prefix = if index == 0 { "" } else { "(inlined by) " };
+        let function = location.function.as_deref().unwrap_or("??");
+        let _ = write!(report, "        {}{}", prefix, function);
+        if let Some(file) = &location.file {
+            let _ = write!(report, " at {}", file);
+            if let Some(line) = location.line {
+                let _ = write!(report, ":{}", line);
+            }
+        }
+        report.push('\n');
+    }
+}
+
+fn parse_checked(text: &str) -> Result<Tombstone, String> {
+    let tombstone = parse_tombstone_text(text);
+    if tombstone.signal.is_none() && tombstone.crashing_thread.backtrace.is_empty() {
+        return Err("Not a tombstone".to_string());
+    }
+    Ok(tombstone)
+}
+
+fn format_report(tombstone: &Tombstone) -> String {
+    let mut report = String::new();
+    let thread = &tombstone.crashing_thread;
+    let _ = writeln!(
+        report,
+        "Native crash in {} (pid {}, tid {}{})",
+        tombstone.process.as_deref().unwrap_or("<unknown process>"),
+        tombstone.pid.map(|p| p.to_string()).unwrap_or("?".into()),
+        thread.tid,
+        thread
+            .name
+            .as_ref()
+            .map(|n| format!(" \"{}\"", n))
+            .unwrap_or_default()
+    );
+    if let Some(signal) = &tombstone.signal {
+        let _ = write!(report, "Signal: {} ({})", signal.name, signal.number);
+        if let Some(code_name) = &signal.code_name {
+            let _ = write!(report, ", {}", code_name);
+        }
+        match signal.fault_addr {
+            Some(addr) => {
+                let _ = writeln!(report, ", fault addr 0x{:x}", addr);
+            }
+            None => report.push('\n'),
+        }
+    }
+    if let Some(cause) = &tombstone.cause {
+        let _ = writeln!(report, "Cause: {}", cause);
+    }
+    if let Some(message) = &tombstone.abort_message {
+        let _ = writeln!(report, "Abort message: {}", message);
+    }
+    if let Some(fingerprint) = &tombstone.build_fingerprint {
+        let _ = writeln!(report, "Build: {}", fingerprint);
+    }
+    if let Some(abi) = &tombstone.abi {
+        let _ = writeln!(report, "ABI: {}", abi);
+    }
+    if let Some(fault) = tombstone.memory_map.iter().find(|m| m.contains_fault) {
+        let _ = writeln!(
+            report,
+            "Fault in mapping: {:x}-{:x} {} {}",
+            fault.start,
+            fault.end,
+            fault.permissions,
+            fault.name.as_deref().unwrap_or("")
+        );
+    }
+
+    report.push_str("\nBacktrace:\n");
+    for frame in &thread.backtrace {
+        format_frame(&mut report, frame);
+    }
+
+    if !thread.registers.is_empty() {
+        report.push_str("\nRegisters:\n");
+        for row in thread.registers.chunks(4) {
+            let line: Vec<String> = row
+                .iter()
+                .map(|(name, value)| format!("{:>4} {:016x}", name, value))
+                .collect();
+            let _ = writeln!(report, "  {}", line.join("  "));
+        }
+    }
+
+    for other in &tombstone.other_threads {
+        let _ = writeln!(
+            report,
+            "\nThread {}{}:",
+            other.tid,
+            other
+                .name
+                .as_ref()
+                .map(|n| format!(" \"{}\"", n))
+                .unwrap_or_default()
+        );
+        for frame in &other.backtrace {
+            format_frame(&mut report, frame);
+        }
+    }
+    report
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn parse_tombstone(text: String) -> Result<Tombstone, String> {
+    parse_checked(&text)
+}
+
+/// Parses `text` and resolves every frame against unstripped libraries found
+/// anywhere below `symbols_dir`, matched by BuildId (or by file name when the
+/// tombstone has no BuildIds). Inlined calls are expanded from DWARF.
+#[tauri::command]
+pub async fn symbolize_tombstone(
+    text: String,
+    symbols_dir: String,
+) -> Result<SymbolizedTombstone, String> {
+    let mut tombstone = parse_checked(&text)?;
+    let symbols_dir = Path::new(&symbols_dir);
+    if !symbols_dir.is_dir() {
+        return Err(format!("{} is not a folder", symbols_dir.display()));
+    }
+    let (by_build_id, by_name) = index_symbol_files(symbols_dir);
+
+    let mut loaders: HashMap<PathBuf, Option<Loader>> = HashMap::new();
+    let mut missing_build_ids = Vec::new();
+    let mut symbolized_frames = 0;
+    let mut total_frames = 0;
+
+    let threads =
+        std::iter::once(&mut tombstone.crashing_thread).chain(tombstone.other_threads.iter_mut());
+    for thread in threads {
+        for frame in &mut thread.backtrace {
+            let Some(library) = frame.library.as_deref() else {
+                continue;
+            };
+            total_frames += 1;
+            let path = match &frame.build_id {
+                Some(build_id) => by_build_id.get(build_id),
+                None => by_name.get(library_file_name(library)),
+            };
+            let Some(path) = path else {
+                if let Some(build_id) = &frame.build_id {
+                    if !missing_build_ids.contains(build_id) {
+                        missing_build_ids.push(build_id.clone());
+                    }
+                }
+                continue;
+            };
+
+            let loader = loaders
+                .entry(path.clone())
+                .or_insert_with(|| Loader::new(path).ok());
+            if let Some(loader) = loader {
+                frame.locations = frame_locations(loader, frame.pc);
+                if !frame.locations.is_empty() {
+                    symbolized_frames += 1;
+                }
+            }
+        }
+    }
+
+    let report = format_report(&tombstone);
+    Ok(SymbolizedTombstone {
+        tombstone,
+        symbolized_frames,
+        total_frames,
+        missing_build_ids,
+        report,
+    })
+}
@@ -1,4 +1,5 @@
 use std::path::PathBuf;
+use std::process::Command;
 use tauri::{AppHandle, Manager};
 
 pub fn resolve_adb_path(app: &AppHandle) -> String {
@@ -65,3 +66,72 @@ pub fn resolve_scrcpy_path(app: &AppHandle) -> PathBuf {
     // Fallback to system PATH
     PathBuf::from("scrcpy")
 }
+
+/// Returns `<app data>/<subdir>`, creating it if needed. Used for state that
+/// must survive restarts, such as journals and saved presets.
+pub fn app_data_subdir(app: &AppHandle, subdir: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data folder: {}", e))?
+        .join(subdir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create folder: {}", e))?;
+    Ok(dir)
+}
+
+/// Turns a device serial (`192.168.1.5:5555`, `emulator-5554`) into a name
+/// that is safe to use as a file name on every platform.
+pub fn file_safe_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Quotes a value for the device shell. `adb shell` joins its arguments with
+/// spaces and hands them to `sh`, so anything user-supplied must be quoted.
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Runs `adb shell <args>` and returns stdout. `am`, `pm` and `cmd` may exit
+/// with 0 after printing an exception or `Error:`, so those fail as well.
+pub fn adb_shell(adb_path: &str, device: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "shell"])
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || stderr.contains("Exception") || stdout.starts_with("Error") {
+        return Err(format!("{}{}", stdout, stderr).trim().to_string());
+    }
+    Ok(stdout)
+}
+
+/// Whether `adb get-state` reports the device as online.
+pub fn device_online(adb_path: &str, device: &str) -> Result<bool, String> {
+    let output = Command::new(adb_path)
+        .args(["-s", device, "get-state"])
+        .output()
+        .map_err(|e| format!("Failed to execute adb: {}", e))?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim() == "device")
+}
+
+/// Returns `<app cache>/<subdir>`, creating it if needed. For data that can
+/// be rebuilt from the device at any time.
+pub fn app_cache_subdir(app: &AppHandle, subdir: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve app cache folder: {}", e))?
+        .join(subdir);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create folder: {}", e))?;
+    Ok(dir)
+}
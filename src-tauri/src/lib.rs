@@ -1,7 +1,11 @@
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 mod commands;
 
-use commands::{adb, diagnostics, files, logs, mirror, packages, terminal};
+use commands::{
+    adb, apk, apk_info, apk_signing, appops, background, crashes, debloat, deeplinks, diagnostics,
+    files, intents, labels, logs, memory, mirror, packages, processes, snapshots, storage,
+    terminal, tombstone,
+};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -36,7 +40,6 @@ pub fn run() {
             files::move_file,
             files::copy_file,
             terminal::run_adb_command,
-            logs::get_adb_logs,
             mirror::start_screen_mirror,
             mirror::check_scrcpy,
             mirror::install_scrcpy,
@@ -49,6 +52,63 @@ pub fn run() {
             packages::clear_package_data,
             packages::force_stop_package,
             packages::launch_package,
+            packages::list_users,
+            packages::install_existing,
+            debloat::debloat_package,
+            debloat::get_debloat_journal,
+            debloat::restore_debloated_package,
+            debloat::restore_all_debloated,
+            debloat::import_debloat_list,
+            debloat::list_debloat_profiles,
+            debloat::apply_debloat_profile,
+            intents::send_intent,
+            intents::save_intent,
+            intents::list_saved_intents,
+            intents::delete_saved_intent,
+            deeplinks::list_deep_links,
+            deeplinks::test_deep_link,
+            deeplinks::get_app_links,
+            deeplinks::set_app_links,
+            deeplinks::verify_app_links,
+            appops::get_app_ops,
+            appops::set_app_op,
+            appops::reset_app_ops,
+            background::get_background_state,
+            background::set_standby_bucket,
+            background::set_battery_optimization_exempt,
+            background::set_background_restricted,
+            background::set_app_inactive,
+            packages::batch_package_action,
+            processes::list_running_packages,
+            storage::get_package_storage,
+            storage::clear_package_cache,
+            snapshots::snapshot_packages,
+            snapshots::list_snapshots,
+            snapshots::get_snapshot,
+            snapshots::delete_snapshot,
+            snapshots::diff_package_snapshots,
+            labels::get_package_labels,
+            memory::get_app_meminfo,
+            memory::start_meminfo_sampling,
+            memory::stop_meminfo_sampling,
+            memory::capture_heap_dump,
+            memory::convert_hprof,
+            crashes::list_crashes,
+            crashes::export_crashes,
+            tombstone::parse_tombstone,
+            tombstone::symbolize_tombstone,
+            logs::start_logcat,
+            logs::pause_logcat,
+            logs::set_logcat_filter,
+            logs::stop_logcat,
+            logs::get_log_buffers,
+            logs::set_log_buffer_size,
+            logs::clear_log_buffers,
+            logs::get_event_logs,
+            apk::extract_apk,
+            apk::install_apk_bundle,
+            apk_info::inspect_apk,
+            apk_signing::verify_apk_signature,
             diagnostics::get_device_diagnostics,
             diagnostics::run_touch_test,
             diagnostics::inject_touch,
@@ -57,6 +117,10 @@ pub fn run() {
             diagnostics::toggle_bluetooth,
             diagnostics::simulate_battery_level,
             diagnostics::reset_battery_simulation,
+            diagnostics::get_doze_state,
+            diagnostics::force_doze,
+            diagnostics::step_doze,
+            diagnostics::unforce_doze,
             diagnostics::trigger_vibration
         ])
         .run(tauri::generate_context!())
@@ -3,9 +3,10 @@ import { cn } from "@/lib/utils";
 import { Button } from "@/components/ui/button";
 import { Square } from "lucide-react";
 import { Virtuoso, VirtuosoHandle } from "react-virtuoso";
+import { LogEntry, LogPriority } from "./types";
 
 interface LogListProps {
-    logs: string[];
+    logs: LogEntry[];
     loading: boolean;
     emptySource: boolean;
     autoScroll: boolean;
@@ -13,59 +14,27 @@ interface LogListProps {
     onScrollChange: (isAtBottom: boolean) => void;
 }
 
-// Regex for standard `adb logcat -v threadtime`
-// Format: date time pid tid level tag: message
-const LOG_REGEX = /^(\d{2}-\d{2}\s+[\d:.]+)\s+(\d+)\s+(\d+)\s+([VDIWEF])\s+(.*?):\s+(.*)$/;
+const LEVEL_LETTER: Record<LogPriority, string> = {
+    verbose: 'V',
+    debug: 'D',
+    info: 'I',
+    warn: 'W',
+    error: 'E',
+    fatal: 'F',
+};
 
-function parseLine(line: string) {
-    const match = line.match(LOG_REGEX);
-    if (!match) return null;
-    return {
-        time: match[1],
-        pid: match[2],
-        tid: match[3],
-        level: match[4],
-        tag: match[5],
-        message: match[6]
-    };
-}
-
-const LogLine = memo(({ line }: { line: string }) => {
-    if (!line.trim()) return null;
-
-    const parsed = parseLine(line);
-
-    // Fallback if regex fails - use the old simple heuristic
-    if (!parsed) {
-        let colorClass = "text-muted-foreground";
-        let bgClass = "transparent";
+const pad = (value: number, width = 2) => value.toString().padStart(width, '0');
 
-        if (line.includes(" E ") || line.startsWith("E/")) {
-            colorClass = "text-destructive";
-            bgClass = "bg-destructive/10 border-l-2 border-destructive/50 pl-3";
-        } else if (line.includes(" W ") || line.startsWith("W/")) {
-            colorClass = "text-yellow-500 dark:text-yellow-400";
-        } else if (line.includes(" I ") || line.startsWith("I/")) {
-            colorClass = "text-blue-500 dark:text-blue-400";
-        } else if (line.includes(" D ") || line.startsWith("D/")) {
-            colorClass = "text-cyan-500 dark:text-cyan-400";
-        } else if (line.includes(" V ") || line.startsWith("V/")) {
-            colorClass = "text-muted-foreground";
-        }
-
-        return (
-            <div className={cn(
-                "whitespace-pre-wrap break-all py-[1px] px-4 text-[12px] leading-[1.6] hover:bg-muted/50 transition-colors border-l-2 border-transparent font-mono",
-                colorClass,
-                bgClass === "transparent" ? "" : bgClass
-            )}>
-                {line}
-            </div>
-        );
-    }
+// Same layout as `logcat -v threadtime`: MM-DD HH:MM:SS.mmm, local time
+function formatTime(timestampMs: number) {
+    const date = new Date(timestampMs);
+    return `${pad(date.getMonth() + 1)}-${pad(date.getDate())} ${pad(date.getHours())}:${pad(date.getMinutes())}:${pad(date.getSeconds())}.${pad(date.getMilliseconds(), 3)}`;
+}
 
-    // Parsed rendering
-    const { time, level, tag, message } = parsed;
+const LogLine = memo(({ entry }: { entry: LogEntry }) => {
+    const time = formatTime(entry.timestamp_ms);
+    const level = LEVEL_LETTER[entry.level];
+    const { tag, message } = entry;
 
     let levelColor = "text-muted-foreground";
     let bgClass = "transparent";
@@ -137,7 +106,7 @@ export function LogList({
                         ref={virtuosoRef}
                         style={{ height: "100%" }}
                         totalCount={logs.length}
-                        itemContent={(index) => <LogLine line={logs[index]} />}
+                        itemContent={(index) => <LogLine entry={logs[index]} />}
                         followOutput={autoScroll ? "auto" : false}
                         atBottomStateChange={(isAtBottom) => {
                             // Only update if the state actually changes to avoid loops
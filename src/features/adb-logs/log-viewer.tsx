@@ -1,68 +1,144 @@
-import { useState, useEffect, useMemo } from "react";
+import { useState, useEffect, useMemo, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import { toast } from "sonner";
 import { useDeviceStore } from "@/store/device-store";
 import { useSettingsStore } from "@/store/settings-store";
 import { LogToolbar } from "./log-toolbar";
 import { LogList } from "./log-list";
-import { LogLevel } from "./types";
+import { LogEntry, LogLevel, LogPriority, LogcatBatch, LogcatStatus } from "./types";
+
+// Oldest entries are dropped beyond this
+const MAX_ENTRIES = 5000;
+
+const PRIORITY_WEIGHT: Record<LogPriority, number> = {
+    verbose: 0,
+    debug: 1,
+    info: 2,
+    warn: 3,
+    error: 4,
+    fatal: 4,
+};
+
+const LEVEL_WEIGHT: Record<LogLevel, number> = {
+    V: 0,
+    D: 1,
+    I: 2,
+    W: 3,
+    E: 4,
+};
 
 export function LogViewer() {
     const selectedSerial = useDeviceStore((state) => state.selectedSerial);
     const defaultLogLevel = useSettingsStore((state) => state.defaultLogLevel);
-    const [logs, setLogs] = useState<string>("");
+    const [entries, setEntries] = useState<LogEntry[]>([]);
     const [loading, setLoading] = useState(false);
-    const [isLive, setIsLive] = useState(false);
+    const [isLive, setIsLive] = useState(true);
     const [autoScroll, setAutoScroll] = useState(true);
+    // Bumped to restart the session, which reloads the recent lines
+    const [restartKey, setRestartKey] = useState(0);
+    const sessionRef = useRef<number | null>(null);
 
     // Filters
     const [filterText, setFilterText] = useState("");
     const [minLevel, setMinLevel] = useState<LogLevel>(defaultLogLevel);
 
-    const fetchLogs = async (silent = false) => {
+    // One logcat session per device, stopped on device change and unmount
+    useEffect(() => {
+        setEntries([]);
         if (!selectedSerial) return;
-        if (!silent) setLoading(true);
-        try {
-            const res = await invoke<string>("get_adb_logs", { device: selectedSerial });
-            setLogs(res);
-        } catch (err) {
-            console.error(err);
-            if (!silent) setLogs(`Error fetching logs: ${err}`);
-        } finally {
-            if (!silent) setLoading(false);
-        }
-    };
 
-    // Initial load
-    useEffect(() => {
-        if (selectedSerial) {
-            fetchLogs();
-            setIsLive(false); // Reset live on device change
-        } else {
-            setLogs("");
-        }
-    }, [selectedSerial]);
+        let cancelled = false;
+        let sessionId: number | null = null;
+        // Batches can arrive before start_logcat returns the session id
+        let early: LogcatBatch[] = [];
+        const unlisteners: UnlistenFn[] = [];
 
-    // Live polling
-    useEffect(() => {
-        let interval: NodeJS.Timeout;
-        if (isLive && selectedSerial) {
-            if (autoScroll) {
-                // Initial fetch when starting live
-                fetchLogs(true);
+        const append = (batch: LogEntry[]) => {
+            setEntries((prev) => {
+                const next = prev.concat(batch);
+                return next.length > MAX_ENTRIES ? next.slice(-MAX_ENTRIES) : next;
+            });
+            setLoading(false);
+        };
+
+        const start = async () => {
+            setLoading(true);
+            setIsLive(true);
+            unlisteners.push(
+                await listen<LogcatBatch>("logcat-entries", ({ payload }) => {
+                    if (sessionId === null) {
+                        early.push(payload);
+                    } else if (payload.session_id === sessionId) {
+                        append(payload.entries);
+                    }
+                }),
+                await listen<LogcatStatus>("logcat-status", ({ payload }) => {
+                    if (payload.session_id !== sessionId) return;
+                    if (payload.state === "running") {
+                        setLoading(false);
+                    } else if (payload.state === "stopped") {
+                        setLoading(false);
+                        setIsLive(false);
+                        sessionRef.current = null;
+                        if (payload.message) toast.error(`Logcat stopped: ${payload.message}`);
+                    }
+                }),
+            );
+            if (cancelled) {
+                // Cleanup already ran while the listeners were registering
+                unlisteners.forEach((unlisten) => unlisten());
+                return;
+            }
+
+            try {
+                const id = await invoke<number>("start_logcat", { device: selectedSerial });
+                if (cancelled) {
+                    invoke("stop_logcat", { sessionId: id }).catch(console.error);
+                    return;
+                }
+                sessionId = id;
+                sessionRef.current = id;
+                early.filter((batch) => batch.session_id === id).forEach((batch) => append(batch.entries));
+                early = [];
+            } catch (err) {
+                console.error(err);
+                setLoading(false);
+                setIsLive(false);
+                toast.error(`Failed to start logcat: ${err}`);
+            }
+        };
+        start();
+
+        return () => {
+            cancelled = true;
+            unlisteners.forEach((unlisten) => unlisten());
+            if (sessionId !== null) {
+                invoke("stop_logcat", { sessionId }).catch(console.error);
             }
-            interval = setInterval(() => {
-                fetchLogs(true);
-            }, 2000);
+            sessionRef.current = null;
+        };
+    }, [selectedSerial, restartKey]);
+
+    const handleToggleLive = async () => {
+        if (sessionRef.current === null) {
+            // The session stopped, e.g. after a logcat error
+            setRestartKey((key) => key + 1);
+            return;
+        }
+        try {
+            await invoke("pause_logcat", { sessionId: sessionRef.current, paused: isLive });
+            setIsLive(!isLive);
+        } catch (err) {
+            toast.error(`Failed to ${isLive ? "pause" : "resume"} logcat: ${err}`);
         }
-        return () => clearInterval(interval);
-    }, [isLive, selectedSerial, autoScroll]);
+    };
 
     const handleClear = async () => {
         if (!selectedSerial) return;
         try {
-            await invoke("run_adb_command", { device: selectedSerial, command: "logcat -c" });
-            setLogs("");
+            await invoke("clear_log_buffers", { device: selectedSerial, buffers: [] });
+            setEntries([]);
             toast.success("Logcat buffer cleared");
         } catch (err) {
             toast.error("Failed to clear logs");
@@ -71,45 +147,24 @@ export function LogViewer() {
 
     // Derived state for filtered logs
     const filteredLogs = useMemo(() => {
-        if (!logs) return [];
-        const lines = logs.split('\n');
-
-        return lines.filter(line => {
-            if (!line.trim()) return false;
+        const text = filterText.toLowerCase();
+        const minWeight = LEVEL_WEIGHT[minLevel];
 
-            // Text Filter
-            if (filterText && !line.toLowerCase().includes(filterText.toLowerCase())) {
+        return entries.filter((entry) => {
+            if (PRIORITY_WEIGHT[entry.level] < minWeight) return false;
+            if (text && !entry.tag.toLowerCase().includes(text) && !entry.message.toLowerCase().includes(text)) {
                 return false;
             }
-
-            // Level Filter
-            // Simple heuristic to detect level
-            let levelWeight = 0; // V
-            if (line.includes(" E ") || line.startsWith("E/")) levelWeight = 4;
-            else if (line.includes(" F ") || line.startsWith("F/")) levelWeight = 4;
-            else if (line.includes(" W ") || line.startsWith("W/")) levelWeight = 3;
-            else if (line.includes(" I ") || line.startsWith("I/")) levelWeight = 2;
-            else if (line.includes(" D ") || line.startsWith("D/")) levelWeight = 1;
-
-            let minWeight = 0;
-            switch (minLevel) {
-                case 'E': minWeight = 4; break;
-                case 'W': minWeight = 3; break;
-                case 'I': minWeight = 2; break;
-                case 'D': minWeight = 1; break;
-                default: minWeight = 0;
-            }
-
-            return levelWeight >= minWeight;
+            return true;
         });
-    }, [logs, filterText, minLevel]);
+    }, [entries, filterText, minLevel]);
 
     return (
         <div className="flex flex-col h-full bg-background">
             <LogToolbar
                 isLive={isLive}
-                onToggleLive={() => setIsLive(!isLive)}
-                onRefresh={() => fetchLogs(false)}
+                onToggleLive={handleToggleLive}
+                onRefresh={() => setRestartKey((key) => key + 1)}
                 onClear={handleClear}
                 loading={loading}
                 hasDevice={!!selectedSerial}
@@ -122,7 +177,7 @@ export function LogViewer() {
             <LogList
                 logs={filteredLogs}
                 loading={loading}
-                emptySource={!logs}
+                emptySource={entries.length === 0}
                 autoScroll={autoScroll}
                 onResumeAutoScroll={() => setAutoScroll(true)}
                 onScrollChange={setAutoScroll}
@@ -130,4 +185,3 @@ export function LogViewer() {
         </div>
     );
 }
-
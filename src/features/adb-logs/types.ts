@@ -1,11 +1,28 @@
 export type LogLevel = 'V' | 'D' | 'I' | 'W' | 'E';
 
+// Shapes of the `logcat-entries` and `logcat-status` events
+export type LogPriority = 'verbose' | 'debug' | 'info' | 'warn' | 'error' | 'fatal';
+
 export interface LogEntry {
-    raw: string;
-    timestamp?: string;
-    pid?: string;
-    tid?: string;
-    level: LogLevel;
-    tag?: string;
+    timestamp_ms: number;
+    pid: number;
+    tid: number;
+    uid: number | null;
+    level: LogPriority;
+    tag: string;
     message: string;
+    buffer: string | null;
+}
+
+export interface LogcatBatch {
+    session_id: number;
+    entries: LogEntry[];
+}
+
+export type LogcatState = 'running' | 'paused' | 'reconnecting' | 'stopped';
+
+export interface LogcatStatus {
+    session_id: number;
+    state: LogcatState;
+    message: string | null;
 }
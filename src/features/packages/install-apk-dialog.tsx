@@ -7,6 +7,24 @@ import { Button } from "@/components/ui/button";
 import { Separator } from "@/components/ui/separator";
 import { Upload, FileUp, X, AlertCircle, CheckCircle, Loader2 } from "lucide-react";
 
+// Mirrors `InstallError` in src-tauri/src/commands/apk.rs
+type InstallError =
+    | { kind: "failure"; code: string; message: string; description: string | null }
+    | { kind: "adb"; message: string };
+
+const formatInstallError = (err: unknown): string => {
+    if (err instanceof Error) return err.message;
+    if (typeof err === "object" && err !== null && "kind" in err) {
+        const installError = err as InstallError;
+        if (installError.kind === "failure") {
+            const detail = installError.description || installError.message;
+            return detail ? `${installError.code}: ${detail}` : installError.code;
+        }
+        return installError.message;
+    }
+    return String(err);
+};
+
 interface InstallApkDialogProps {
     open: boolean;
     onOpenChange: (open: boolean) => void;
@@ -104,7 +122,7 @@ export function InstallApkDialog({ open: isOpen, onOpenChange, deviceSerial, onI
                 onOpenChange(false);
             }, 1500);
         } catch (err) {
-            setError(err instanceof Error ? err.message : String(err));
+            setError(formatInstallError(err));
         } finally {
             setInstalling(false);
         }
@@ -56,6 +56,7 @@ export const usePackageStore = create<PackageState>((set) => ({
       const info = await invoke<PackageDetails>("get_package_details", {
         device: deviceSerial,
         package: pkgId,
+        includeStorage: true,
       });
       set({ details: info, detailsLoading: false });
     } catch (err) {